@@ -0,0 +1,182 @@
+use std::collections::HashMap;
+use std::error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use parse_error::ParseError;
+use solver::{Answer, Part};
+
+// Known-correct answers, kept in a small TOML subset:
+//
+//     [day2]
+//     part1 = "65556"   # verify compares answers as printed, so 65556 works too
+//     part2 = "CB779"
+//
+// Multi-line answers use a ''' literal block, one row per line.
+
+#[derive(Debug)]
+pub struct Answers {
+    answers: HashMap<(u32, Part), Answer>,
+}
+
+#[derive(Debug)]
+pub enum AnswersError {
+    Io { path: String, error: io::Error },
+    Parse { path: String, error: ParseError },
+}
+
+impl fmt::Display for AnswersError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            AnswersError::Io { ref path, ref error } =>
+                write!(f, "could not read answers {}: {}", path, error),
+            AnswersError::Parse { ref path, ref error } =>
+                write!(f, "answers {}: {}", path, error),
+        }
+    }
+}
+
+impl error::Error for AnswersError {}
+
+// The value without any trailing # comment, which can't start inside a string
+fn strip_comment(value: &str) -> &str {
+    let start = match value.strip_prefix('"') {
+        Some(string) => string.find('"').map_or(value.len(), |i| i + 2),
+        None => 0,
+    };
+    match value[start..].find('#') {
+        Some(i) => value[..start + i].trim_end(),
+        None => value,
+    }
+}
+
+fn parse_value(input: &str, value: &str) -> Result<Answer, ParseError> {
+    if value.starts_with('"') {
+        if value.len() < 2 || !value.ends_with('"') {
+            return Err(ParseError::at(input, &value[value.len()..], "'\"' closing the string"));
+        }
+        Ok(Answer::Text(value[1..value.len() - 1].to_string()))
+    } else {
+        value.parse::<i64>()
+            .map(Answer::Integer)
+            .map_err(|_| ParseError::at(input, value, "an integer, \"string\" or '''"))
+    }
+}
+
+impl Answers {
+    pub fn load(path: &Path) -> Result<Answers, AnswersError> {
+        let text = fs::read_to_string(path)
+            .map_err(|error| AnswersError::Io { path: path.display().to_string(), error })?;
+        Answers::parse(&text)
+            .map_err(|error| AnswersError::Parse { path: path.display().to_string(), error })
+    }
+
+    pub fn parse(input: &str) -> Result<Answers, ParseError> {
+        let mut answers = HashMap::new();
+        let mut day = None;
+        let mut lines = input.lines();
+
+        while let Some(line) = lines.next() {
+            let trimmed = line.trim();
+            if trimmed.is_empty() || trimmed.starts_with('#') {
+                continue;
+            }
+
+            if trimmed.starts_with('[') {
+                let header = trimmed.trim_start_matches('[').trim_end_matches(']');
+                if !header.starts_with("day") || !trimmed.ends_with(']') {
+                    return Err(ParseError::at(input, trimmed, "a [dayN] header"));
+                }
+                let n = &header[3..];
+                day = Some(n.parse::<u32>()
+                    .map_err(|_| ParseError::at(input, n, "a day number"))?);
+                continue;
+            }
+
+            let eq = trimmed.find('=')
+                .ok_or_else(|| ParseError::at(input, trimmed, "'partN = answer'"))?;
+            let key = trimmed[..eq].trim();
+            let part = match key {
+                "part1" => Part::One,
+                "part2" => Part::Two,
+                _ => return Err(ParseError::at(input, key, "part1 or part2")),
+            };
+            let day = day.ok_or_else(|| ParseError::at(input, key, "a [dayN] header first"))?;
+
+            let value = strip_comment(trimmed[eq + 1..].trim());
+            let answer = if value == "'''" {
+                let mut rows = Vec::new();
+                loop {
+                    match lines.next() {
+                        Some(row) if row.trim_end() == "'''" => break,
+                        Some(row) => rows.push(row.to_string()),
+                        None => return Err(ParseError::at(input, &input[input.len()..],
+                                                          "''' closing the block")),
+                    }
+                }
+                Answer::Grid(rows)
+            } else {
+                parse_value(input, value)?
+            };
+
+            answers.insert((day, part), answer);
+        }
+
+        Ok(Answers { answers })
+    }
+
+    pub fn get(&self, day: u32, part: Part) -> Option<&Answer> {
+        self.answers.get(&(day, part))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_values() {
+        let answers = Answers::parse(r#"
+# answers
+[day1]
+part1 = 226
+part2 = -3  # a note
+
+[day12]
+part1 = "CB779"
+part2 = "a # b" # not part of it
+"#).unwrap();
+        assert_eq!(answers.get(1, Part::One), Some(&Answer::Integer(226)));
+        assert_eq!(answers.get(1, Part::Two), Some(&Answer::Integer(-3)));
+        assert_eq!(answers.get(12, Part::One), Some(&Answer::Text("CB779".to_string())));
+        assert_eq!(answers.get(12, Part::Two), Some(&Answer::Text("a # b".to_string())));
+        assert_eq!(answers.get(2, Part::One), None);
+    }
+
+    #[test]
+    fn parses_blocks() {
+        let answers = Answers::parse("[day8]\npart2 = '''\n#..#\n # #\n'''\npart1 = 7\n").unwrap();
+        assert_eq!(answers.get(8, Part::Two), Some(&Answer::Grid(vec!["#..#".to_string(), " # #".to_string()])));
+        assert_eq!(answers.get(8, Part::One), Some(&Answer::Integer(7)));
+    }
+
+    fn error(input: &str) -> (usize, usize, String, String) {
+        let e = Answers::parse(input).unwrap_err();
+        (e.line, e.column, e.token, e.expected)
+    }
+
+    #[test]
+    fn error_positions() {
+        let expect = |line, column, token: &str, expected: &str| (line, column, token.to_string(), expected.to_string());
+        assert_eq!(error("[week1]"), expect(1, 1, "[week1]", "a [dayN] header"));
+        assert_eq!(error("[dayx]"), expect(1, 5, "x", "a day number"));
+        assert_eq!(error("part1 = 3"), expect(1, 1, "part1", "a [dayN] header first"));
+        assert_eq!(error("[day1]\npart3 = 3"), expect(2, 1, "part3", "part1 or part2"));
+        assert_eq!(error("[day1]\n  part1 3"), expect(2, 3, "part1 3", "'partN = answer'"));
+        assert_eq!(error("[day1]\npart1 = 3x"), expect(2, 9, "3x", "an integer, \"string\" or '''"));
+        assert_eq!(error("[day1]\npart1 = \"abc"), expect(2, 13, "", "'\"' closing the string"));
+        assert_eq!(error("[day1]\npart1 = '''\nrow"), expect(3, 4, "", "''' closing the block"));
+    }
+}
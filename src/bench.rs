@@ -0,0 +1,229 @@
+use std::fmt::Display;
+use std::time::{Duration, Instant};
+
+use parse_error::ParseError;
+use profile::Profile;
+use registry::Day;
+use solver::Part;
+use timing::format_duration;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Format {
+    Table,
+    Json,
+    Csv,
+}
+
+impl Format {
+    pub fn from_name(name: &str) -> Option<Format> {
+        match name {
+            "table" => Some(Format::Table),
+            "json" => Some(Format::Json),
+            "csv" => Some(Format::Csv),
+            _ => None,
+        }
+    }
+}
+
+pub struct Stats {
+    pub profile: String,
+    pub day: u32,
+    pub part: Part,
+    pub runs: usize,
+    pub min: Duration,
+    pub median: Duration,
+    pub p95: Duration,
+    pub input_bytes: usize,
+}
+
+impl Stats {
+    // input bytes consumed per second, at the median run time, or None if
+    // that's too quick for the clock to measure
+    pub fn throughput(&self) -> Option<f64> {
+        if self.median == Duration::from_secs(0) {
+            return None;
+        }
+        Some(self.input_bytes as f64 / self.median.as_secs_f64())
+    }
+}
+
+// Nearest-rank percentile of already sorted samples
+fn percentile(sorted: &[Duration], p: f64) -> Duration {
+    let rank = (p / 100.0 * sorted.len() as f64).ceil() as usize;
+    sorted[rank.max(1) - 1]
+}
+
+pub fn median(sorted: &[Duration]) -> Duration {
+    let mid = sorted.len() / 2;
+    if sorted.len().is_multiple_of(2) {
+        (sorted[mid - 1] + sorted[mid]) / 2
+    } else {
+        sorted[mid]
+    }
+}
+
+// --runs for every command that times something
+pub fn parse_runs(value: &str) -> Result<usize, String> {
+    match value.parse::<usize>() {
+        Ok(0) => Err("--runs must be at least 1".to_string()),
+        Ok(runs) => Ok(runs),
+        Err(_) => Err(format!("--runs expects a number, got '{}'", value)),
+    }
+}
+
+// Times `runs` calls of `run`, returning the sorted samples and the result
+// of the last call
+pub fn time<T, F: FnMut() -> T>(runs: usize, mut run: F) -> (Vec<Duration>, T) {
+    let mut samples = Vec::with_capacity(runs);
+    let mut result = None;
+    for _ in 0..runs {
+        let start = Instant::now();
+        result = Some(run());
+        samples.push(start.elapsed());
+    }
+    samples.sort();
+    (samples, result.expect("runs must be at least 1"))
+}
+
+// Interchangeable ways of computing the same thing, for compare() to time
+// against each other
+pub trait Variant: Copy + 'static {
+    const ALL: &'static [Self];
+
+    fn name(self) -> &'static str;
+
+    fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.iter().cloned().find(|v| v.name() == name)
+    }
+}
+
+// Times each variant and prints its fastest and median run with its answer
+pub fn compare<V: Variant, T: Display, F: FnMut(V) -> T>(variants: &[V], runs: usize, mut run: F) {
+    println!("engine            min      median  answer");
+    for &variant in variants {
+        let (samples, answer) = time(runs, || run(variant));
+        println!("{:<8}  {:>10}  {:>10}  {}",
+            variant.name(), format_duration(samples[0]), format_duration(median(&samples)), answer);
+    }
+}
+
+// Every variant's answer, for tests that they all agree
+#[cfg(test)]
+pub fn each<V: Variant, T, F: FnMut(V) -> T>(run: F) -> Vec<T> {
+    V::ALL.iter().cloned().map(run).collect()
+}
+
+// An error on the first run is an error on every run, so there's no need to
+// stop timing early
+pub fn measure(profile: &Profile, day: &Day, part: Part, input: &str, runs: usize)
+    -> Result<Stats, ParseError> {
+    let (samples, answer) = time(runs, || day.solver.solve(part, input));
+    answer?;
+
+    Ok(Stats {
+        profile: profile.to_string(),
+        day: day.day,
+        part,
+        runs,
+        min: samples[0],
+        median: median(&samples),
+        p95: percentile(&samples, 95.0),
+        input_bytes: input.len(),
+    })
+}
+
+fn format_throughput(bytes_per_sec: Option<f64>) -> String {
+    let bytes_per_sec = match bytes_per_sec {
+        Some(b) => b,
+        None => return "-".to_string(),
+    };
+    if bytes_per_sec >= 1e6 {
+        format!("{:.1} MB/s", bytes_per_sec / 1e6)
+    } else if bytes_per_sec >= 1e3 {
+        format!("{:.1} kB/s", bytes_per_sec / 1e3)
+    } else {
+        format!("{:.1} B/s", bytes_per_sec)
+    }
+}
+
+pub fn report(stats: &[Stats], format: Format) {
+    match format {
+        Format::Table => {
+            let width = stats.iter().map(|s| s.profile.len()).max().unwrap_or(0).max(7);
+            println!("{:<width$}  day  part  runs         min      median         p95   throughput",
+                "profile", width = width);
+            for s in stats {
+                println!("{:<width$}  {:>3}  {:>4}  {:>4}  {:>10}  {:>10}  {:>10}  {:>11}",
+                    s.profile, s.day, s.part, s.runs,
+                    format_duration(s.min), format_duration(s.median), format_duration(s.p95),
+                    format_throughput(s.throughput()), width = width);
+            }
+        },
+        Format::Csv => {
+            println!("profile,day,part,runs,min_s,median_s,p95_s,input_bytes,bytes_per_s");
+            for s in stats {
+                println!("{},{},{},{},{:.9},{:.9},{:.9},{},{}",
+                    s.profile, s.day, s.part, s.runs,
+                    s.min.as_secs_f64(), s.median.as_secs_f64(), s.p95.as_secs_f64(),
+                    s.input_bytes, s.throughput().map_or(String::new(), |b| format!("{:.1}", b)));
+            }
+        },
+        Format::Json => {
+            let rows: Vec<String> = stats.iter().map(|s| {
+                format!("  {{\"profile\": \"{}\", \"day\": {}, \"part\": {}, \"runs\": {}, \
+                         \"min_s\": {:.9}, \"median_s\": {:.9}, \"p95_s\": {:.9}, \
+                         \"input_bytes\": {}, \"bytes_per_s\": {}}}",
+                    s.profile, s.day, s.part, s.runs,
+                    s.min.as_secs_f64(), s.median.as_secs_f64(), s.p95.as_secs_f64(),
+                    s.input_bytes, s.throughput().map_or("null".to_string(), |b| format!("{:.1}", b)))
+            }).collect();
+            println!("[\n{}\n]", rows.join(",\n"));
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ms(values: &[u64]) -> Vec<Duration> {
+        values.iter().map(|&v| Duration::from_millis(v)).collect()
+    }
+
+    #[test]
+    fn nearest_rank_percentiles() {
+        let samples = ms(&(1..=20).collect::<Vec<_>>());
+        assert_eq!(percentile(&samples, 95.0), Duration::from_millis(19));
+        assert_eq!(percentile(&samples, 100.0), Duration::from_millis(20));
+        assert_eq!(percentile(&ms(&[1, 2, 3, 4, 5, 6, 7, 8, 9, 10]), 95.0), Duration::from_millis(10));
+        assert_eq!(percentile(&ms(&[7]), 95.0), Duration::from_millis(7));
+        assert_eq!(percentile(&ms(&[7, 8]), 0.0), Duration::from_millis(7));
+    }
+
+    #[test]
+    fn medians() {
+        assert_eq!(median(&ms(&[5])), Duration::from_millis(5));
+        assert_eq!(median(&ms(&[1, 2, 9])), Duration::from_millis(2));
+        assert_eq!(median(&ms(&[1, 2, 4, 9])), Duration::from_millis(3));
+    }
+
+    #[test]
+    fn runs() {
+        assert_eq!(parse_runs("3"), Ok(3));
+        assert_eq!(parse_runs("0"), Err("--runs must be at least 1".to_string()));
+        assert_eq!(parse_runs("-1"), Err("--runs expects a number, got '-1'".to_string()));
+    }
+
+    #[test]
+    fn throughput() {
+        let mut stats = Stats {
+            profile: "default".to_string(), day: 1, part: Part::One, runs: 1,
+            min: Duration::from_secs(0), median: Duration::from_secs(0), p95: Duration::from_secs(0),
+            input_bytes: 2_000_000,
+        };
+        assert_eq!(stats.throughput(), None);
+        assert_eq!(format_throughput(stats.throughput()), "-");
+        stats.median = Duration::from_millis(500);
+        assert_eq!(format_throughput(stats.throughput()), "4.0 MB/s");
+    }
+}
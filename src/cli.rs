@@ -0,0 +1,742 @@
+use std::fmt;
+
+use bench::{self, Format, Variant};
+use columns::Grouping;
+use get_input::Source;
+use keypad_trace::Style;
+use profile::{Profile, Profiles};
+use segments;
+use turtle::Grammar;
+use solver::Part;
+use triangle_batch;
+use triangle_report;
+
+pub const USAGE: &str = "\
+usage: advent2016 run --day <N> [--part <1|2>] [--profile <name>]
+                     [--input <path|-> | --input-text <text>]
+       advent2016 run --all [--part <1|2>] [--profile <name>]
+       advent2016 verify [--day <N>] [--profile <name> | --all-profiles] [--answers <path>]
+       advent2016 path [--logo <path|->] [--svg <path|->] [--csv <path|->]
+                       [--stats] [--map [--size <WxH>]]
+                       [--profile <name>] [--input <path|-> | --input-text <text>]
+       advent2016 walk [--grammar <compass|turtle>] [--profile <name>]
+                       [--input <path|-> | --input-text <text>]
+       advent2016 keypad [--layout <square|diamond|path>] [--start <key>]
+                         [--reach [--code <code>] | --for-code <code>
+                          | --trace <animate|heatmap> [--delay <ms>] | --stream]
+                         [--profile <name>] [--input <path|-> | --input-text <text>]
+       advent2016 triangles [--part <1|2> | --grouping <grouping>] [--format <table|csv>] [--summary]
+                            [--profile <name>] [--input <path|-> | --input-text <text>]
+       advent2016 rooms [--profile <name>] [--input <path|-> | --input-text <text>]
+       advent2016 crossings [--moves <N>] [--length <N>] [--runs <N>] [--engine <name>]
+       advent2016 validate [--rows <N>] [--part <1|2> | --grouping <grouping>] [--runs <N>] [--engine <lines|batch>]
+       advent2016 bench [--day <N>] [--part <1|2>] [--profile <name> | --all-profiles]
+                        [--runs <N>] [--format <table|json|csv>]
+
+commands:
+    run        solve one day, or every day with --all, both parts unless --part is given
+    verify     check every day, or just --day, against the recorded answers
+    path       export day 1's walked path as a Logo program, an SVG drawing or CSV vertices,
+               print its statistics or draw it as an ASCII map
+    walk       follow day 1 style directions, or with --grammar turtle 45 degree turns and 3D moves
+    keypad     type day 2's door code on any keypad layout
+    triangles  classify day 3's candidate triangles with their perimeter and area
+    rooms      list day 4's real rooms by sector ID with their decrypted names
+    crossings  time day 1's revisit engines on a synthetic spiral path
+    validate   time day 3's line by line and batch validation on a generated table
+    bench      time every day, or just --day, over --runs runs (default 10)
+
+options:
+    --profile <name>       use the inputs and answers under assets/<name>/
+    --all-profiles         verify or bench the default profile and every assets/<name>/
+    --input <path|->       read the puzzle input from a file, or stdin for -
+    --input-text <text>    use the given text as the puzzle input
+    --answers <path>       answers file to verify against (default <profile>/answers.toml)
+    --format <format>      bench report as a table (default), json or csv; triangles as a table or csv
+    --grouping <grouping>  read triangles along rows (part 1), down columns three rows at a time
+                           (columns, part 2), down columns N rows at a time (columns:<N>) or
+                           down each whole column in turn (transposed)
+    --summary              count the triangles in each class instead of listing them
+    --logo, --svg, --csv   file to write the path to, or stdout for -
+    --size <WxH>           map size in characters (default $COLUMNS x $LINES, or 80x24)
+    --grammar <grammar>    compass (default, day 1's own) or turtle (L45, R90, F2, B2, U2, D2)
+    --layout <layout>      keypad grid file, spaces for gaps, or square (default) or diamond
+    --start <key>          key the finger starts on (default 5)
+    --reach                list the shortest route from the start key to every key
+    --code <code>          also the shortest moves to type a code, one group per key
+    --for-code <code>      print the shortest input that types the code, checked by solving it
+    --trace <style>        animate every move of the finger, or show a heatmap of each line
+    --delay <ms>           time between animation frames (default 150)
+    --stream               read --input a byte at a time, printing each key as its line ends
+    --engine <name>        only time walk, pairwise or sweep (default all three) for crossings,
+                           lines or batch (default both) for validate
+    --rows <N>             rows in the generated table (default 3000000)";
+
+pub enum Command {
+    Run {
+        day: u32,
+        part: Option<Part>,
+        input: Option<Source>,
+        profile: Profile,
+    },
+    RunAll {
+        part: Option<Part>,
+        profile: Profile,
+    },
+    Verify {
+        day: Option<u32>,
+        answers: Option<String>,
+        profiles: Profiles,
+    },
+    Path {
+        input: Option<Source>,
+        profile: Profile,
+        outputs: PathOutputs,
+    },
+    Walk {
+        input: Option<Source>,
+        profile: Profile,
+        grammar: Grammar,
+    },
+    Keypad {
+        input: Option<Source>,
+        profile: Profile,
+        layout: String,
+        start: char,
+        mode: KeypadMode,
+    },
+    Triangles {
+        input: Option<Source>,
+        profile: Profile,
+        grouping: Grouping,
+        format: triangle_report::Format,
+        summary: bool,
+    },
+    Rooms {
+        input: Option<Source>,
+        profile: Profile,
+    },
+    Crossings {
+        moves: usize,
+        length: u32,
+        runs: usize,
+        engines: Vec<segments::Engine>,
+    },
+    Validate {
+        rows: usize,
+        groupings: Vec<Grouping>,
+        runs: usize,
+        engines: Vec<triangle_batch::Engine>,
+    },
+    Bench {
+        day: Option<u32>,
+        part: Option<Part>,
+        runs: usize,
+        format: Format,
+        profiles: Profiles,
+    },
+}
+
+pub enum KeypadMode {
+    Code,
+    Reach { code: Option<String> },
+    ForCode(String),
+    Trace(Style),
+    Stream,
+}
+
+#[derive(Default)]
+pub struct PathOutputs {
+    pub logo: Option<String>,
+    pub svg: Option<String>,
+    pub csv: Option<String>,
+    pub stats: bool,
+    pub map: bool,
+    pub size: Option<(usize, usize)>,
+}
+
+#[derive(Debug)]
+pub struct UsageError(String);
+
+impl fmt::Display for UsageError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+fn error<T>(msg: String) -> Result<T, UsageError> {
+    Err(UsageError(msg))
+}
+
+fn flag_value<I: Iterator<Item = String>>(flag: &str, args: &mut I) -> Result<String, UsageError> {
+    match args.next() {
+        Some(value) => Ok(value),
+        None => error(format!("{} requires a value", flag)),
+    }
+}
+
+fn parse_number(flag: &str, value: &str) -> Result<u32, UsageError> {
+    match value.parse::<u32>() {
+        Ok(n) => Ok(n),
+        Err(_) => error(format!("{} expects a number, got '{}'", flag, value)),
+    }
+}
+
+fn parse_part(flag: &str, value: &str) -> Result<Part, UsageError> {
+    match Part::from_number(parse_number(flag, value)?) {
+        Some(p) => Ok(p),
+        None => error(format!("{} must be 1 or 2, got '{}'", flag, value)),
+    }
+}
+
+fn parse_size(flag: &str, value: &str) -> Result<(usize, usize), UsageError> {
+    let mut dims = value.splitn(2, 'x').map(|n| n.parse::<usize>());
+    match (dims.next(), dims.next()) {
+        (Some(Ok(w)), Some(Ok(h))) if w > 0 && h > 0 => Ok((w, h)),
+        _ => error(format!("{} expects a size like 80x24, got '{}'", flag, value)),
+    }
+}
+
+fn parse_key(flag: &str, value: &str) -> Result<char, UsageError> {
+    let mut chars = value.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) if c != ' ' => Ok(c),
+        _ => error(format!("{} expects a single key label, got '{}'", flag, value)),
+    }
+}
+
+// Day 3's two parts are just its two groupings, so only one can be given
+fn part_or_grouping(part: Option<Part>, grouping: Option<Grouping>) -> Result<Option<Grouping>, UsageError> {
+    match (part, grouping) {
+        (Some(_), Some(_)) => error("only one of --part and --grouping can be given".to_string()),
+        (Some(Part::One), None) => Ok(Some(Grouping::Rows)),
+        (Some(Part::Two), None) => Ok(Some(Grouping::Columns(3))),
+        (None, grouping) => Ok(grouping),
+    }
+}
+
+fn parse_grouping(flag: &str, value: &str) -> Result<Grouping, UsageError> {
+    match Grouping::from_name(value) {
+        Some(g) => Ok(g),
+        None => error(format!("{} must be rows, columns, columns:<N> or transposed, got '{}'", flag, value)),
+    }
+}
+
+// The runs check itself lives in bench, shared by every timing command
+fn parse_runs(value: &str) -> Result<usize, UsageError> {
+    bench::parse_runs(value).map_err(UsageError)
+}
+
+// walk, pairwise or sweep; lines or batch
+fn parse_engine<V: Variant>(flag: &str, value: &str) -> Result<V, UsageError> {
+    match V::from_name(value) {
+        Some(engine) => Ok(engine),
+        None => {
+            let names: Vec<&str> = V::ALL.iter().map(|v| v.name()).collect();
+            let (last, rest) = names.split_last().expect("every variant type has a variant");
+            error(format!("{} must be {} or {}, got '{}'", flag, rest.join(", "), last, value))
+        },
+    }
+}
+
+fn parse_profile(flag: &str, value: &str) -> Result<Profile, UsageError> {
+    match Profile::named(value) {
+        Some(profile) => Ok(profile),
+        None => error(format!("{} '{}' is not a valid profile name", flag, value)),
+    }
+}
+
+// --profile, --input and --input-text, for every command that reads a puzzle input
+struct InputFlags {
+    input: Option<Source>,
+    profile: Profile,
+}
+
+impl InputFlags {
+    fn new() -> InputFlags {
+        InputFlags { input: None, profile: Profile::default_profile() }
+    }
+
+    // Takes the value of any of the three flags, returning whether `arg` was one
+    fn parse<I: Iterator<Item = String>>(&mut self, arg: &str, args: &mut I) -> Result<bool, UsageError> {
+        match arg {
+            "--profile" => self.profile = parse_profile(arg, &flag_value(arg, args)?)?,
+            "--input" => self.input = Some(Source::from_arg(&flag_value(arg, args)?)),
+            "--input-text" => self.input = Some(Source::Inline(flag_value(arg, args)?)),
+            _ => return Ok(false),
+        }
+        Ok(true)
+    }
+}
+
+fn select_profiles(profile: Option<Profile>, all_profiles: bool) -> Result<Profiles, UsageError> {
+    match (profile, all_profiles) {
+        (Some(_), true) => error("--profile and --all-profiles cannot be combined".to_string()),
+        (Some(profile), false) => Ok(Profiles::One(profile)),
+        (None, true) => Ok(Profiles::All),
+        (None, false) => Ok(Profiles::One(Profile::default_profile())),
+    }
+}
+
+fn parse_run<I: Iterator<Item = String>>(mut args: I) -> Result<Command, UsageError> {
+    let mut day = None;
+    let mut part = None;
+    let mut all = false;
+    let mut inputs = InputFlags::new();
+
+    while let Some(arg) = args.next() {
+        if inputs.parse(&arg, &mut args)? {
+            continue;
+        }
+        match arg.as_str() {
+            "--all" => all = true,
+            "--day" => {
+                let value = flag_value(&arg, &mut args)?;
+                day = Some(parse_number(&arg, &value)?);
+            },
+            "--part" => {
+                let value = flag_value(&arg, &mut args)?;
+                part = Some(parse_part(&arg, &value)?);
+            },
+            _ => return error(format!("unexpected argument '{}'", arg)),
+        }
+    }
+
+    let InputFlags { input, profile } = inputs;
+
+    match (all, day) {
+        (true, Some(_)) => error("--all and --day cannot be combined".to_string()),
+        (true, None) if input.is_some() => error("--all always uses each day's own input".to_string()),
+        (true, None) => Ok(Command::RunAll { part, profile }),
+        (false, Some(day)) => Ok(Command::Run { day, part, input, profile }),
+        (false, None) => error("run requires --day or --all".to_string()),
+    }
+}
+
+fn parse_verify<I: Iterator<Item = String>>(mut args: I) -> Result<Command, UsageError> {
+    let mut day = None;
+    let mut answers = None;
+    let mut profile = None;
+    let mut all_profiles = false;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--profile" => profile = Some(parse_profile(&arg, &flag_value(&arg, &mut args)?)?),
+            "--all-profiles" => all_profiles = true,
+            "--day" => {
+                let value = flag_value(&arg, &mut args)?;
+                day = Some(parse_number(&arg, &value)?);
+            },
+            "--answers" => answers = Some(flag_value(&arg, &mut args)?),
+            _ => return error(format!("unexpected argument '{}'", arg)),
+        }
+    }
+
+    if answers.is_some() && all_profiles {
+        return error("--answers cannot be combined with --all-profiles".to_string());
+    }
+    let profiles = select_profiles(profile, all_profiles)?;
+
+    Ok(Command::Verify { day, answers, profiles })
+}
+
+fn parse_path<I: Iterator<Item = String>>(mut args: I) -> Result<Command, UsageError> {
+    let mut inputs = InputFlags::new();
+    let mut outputs = PathOutputs::default();
+
+    while let Some(arg) = args.next() {
+        if inputs.parse(&arg, &mut args)? {
+            continue;
+        }
+        match arg.as_str() {
+            "--logo" => outputs.logo = Some(flag_value(&arg, &mut args)?),
+            "--svg" => outputs.svg = Some(flag_value(&arg, &mut args)?),
+            "--csv" => outputs.csv = Some(flag_value(&arg, &mut args)?),
+            "--stats" => outputs.stats = true,
+            "--map" => outputs.map = true,
+            "--size" => {
+                let value = flag_value(&arg, &mut args)?;
+                outputs.size = Some(parse_size(&arg, &value)?);
+            },
+            _ => return error(format!("unexpected argument '{}'", arg)),
+        }
+    }
+
+    let InputFlags { input, profile } = inputs;
+
+    if outputs.logo.is_none() && outputs.svg.is_none() && outputs.csv.is_none()
+        && !outputs.stats && !outputs.map {
+        return error("path requires at least one of --logo, --svg, --csv, --stats or --map".to_string());
+    }
+    if outputs.size.is_some() && !outputs.map {
+        return error("--size only applies to --map".to_string());
+    }
+
+    Ok(Command::Path { input, profile, outputs })
+}
+
+fn parse_walk<I: Iterator<Item = String>>(mut args: I) -> Result<Command, UsageError> {
+    let mut inputs = InputFlags::new();
+    let mut grammar = Grammar::Compass;
+
+    while let Some(arg) = args.next() {
+        if inputs.parse(&arg, &mut args)? {
+            continue;
+        }
+        match arg.as_str() {
+            "--grammar" => {
+                let value = flag_value(&arg, &mut args)?;
+                grammar = match Grammar::from_name(&value) {
+                    Some(g) => g,
+                    None => return error(format!("--grammar must be compass or turtle, got '{}'", value)),
+                };
+            },
+            _ => return error(format!("unexpected argument '{}'", arg)),
+        }
+    }
+
+    let InputFlags { input, profile } = inputs;
+
+    Ok(Command::Walk { input, profile, grammar })
+}
+
+fn parse_keypad<I: Iterator<Item = String>>(mut args: I) -> Result<Command, UsageError> {
+    let mut inputs = InputFlags::new();
+    let mut layout = "square".to_string();
+    let mut start = '5';
+    let mut reach = false;
+    let mut code = None;
+    let mut for_code = None;
+    let mut trace = None;
+    let mut delay = None;
+    let mut stream = false;
+
+    while let Some(arg) = args.next() {
+        if inputs.parse(&arg, &mut args)? {
+            continue;
+        }
+        match arg.as_str() {
+            "--layout" => layout = flag_value(&arg, &mut args)?,
+            "--start" => start = parse_key(&arg, &flag_value(&arg, &mut args)?)?,
+            "--reach" => reach = true,
+            "--code" => code = Some(flag_value(&arg, &mut args)?),
+            "--for-code" => for_code = Some(flag_value(&arg, &mut args)?),
+            "--trace" => trace = Some(flag_value(&arg, &mut args)?),
+            "--stream" => stream = true,
+            "--delay" => {
+                let value = flag_value(&arg, &mut args)?;
+                delay = Some(parse_number(&arg, &value)? as u64);
+            },
+            _ => return error(format!("unexpected argument '{}'", arg)),
+        }
+    }
+
+    let InputFlags { input, profile } = inputs;
+
+    if code.is_some() && !reach {
+        return error("--code only applies to --reach".to_string());
+    }
+    if delay.is_some() && trace.as_deref() != Some("animate") {
+        return error("--delay only applies to --trace animate".to_string());
+    }
+
+    let mode = match (reach, for_code, trace, stream) {
+        (false, None, None, false) => KeypadMode::Code,
+        (true, None, None, false) => KeypadMode::Reach { code },
+        (false, Some(code), None, false) => KeypadMode::ForCode(code),
+        (false, None, None, true) => KeypadMode::Stream,
+        (false, None, Some(style), false) => KeypadMode::Trace(match style.as_str() {
+            "animate" => Style::Animate { delay_ms: delay.unwrap_or(150) },
+            "heatmap" => Style::Heatmap,
+            _ => return error(format!("--trace must be animate or heatmap, got '{}'", style)),
+        }),
+        _ => return error("only one of --reach, --for-code, --trace and --stream can be given".to_string()),
+    };
+    match mode {
+        KeypadMode::Reach { .. } | KeypadMode::ForCode(_) if input.is_some() =>
+            return error("--reach and --for-code don't read any input".to_string()),
+        // the cached input is read whole anyway, so streaming it gains nothing
+        KeypadMode::Stream if input.is_none() =>
+            return error("--stream requires --input or --input-text".to_string()),
+        _ => {},
+    }
+
+    Ok(Command::Keypad { input, profile, layout, start, mode })
+}
+
+fn parse_triangles<I: Iterator<Item = String>>(mut args: I) -> Result<Command, UsageError> {
+    let mut inputs = InputFlags::new();
+    let mut part = None;
+    let mut grouping = None;
+    let mut format = None;
+    let mut summary = false;
+
+    while let Some(arg) = args.next() {
+        if inputs.parse(&arg, &mut args)? {
+            continue;
+        }
+        match arg.as_str() {
+            "--summary" => summary = true,
+            "--part" => {
+                let value = flag_value(&arg, &mut args)?;
+                part = Some(parse_part(&arg, &value)?);
+            },
+            "--grouping" => grouping = Some(parse_grouping(&arg, &flag_value(&arg, &mut args)?)?),
+            "--format" => {
+                let value = flag_value(&arg, &mut args)?;
+                format = match triangle_report::Format::from_name(&value) {
+                    Some(f) => Some(f),
+                    None => return error(format!("--format must be table or csv, got '{}'", value)),
+                };
+            },
+            _ => return error(format!("unexpected argument '{}'", arg)),
+        }
+    }
+
+    let InputFlags { input, profile } = inputs;
+
+    if summary && format == Some(triangle_report::Format::Csv) {
+        return error("--summary is only printed as a table".to_string());
+    }
+    let format = format.unwrap_or(triangle_report::Format::Table);
+    let grouping = part_or_grouping(part, grouping)?.unwrap_or(Grouping::Rows);
+
+    Ok(Command::Triangles { input, profile, grouping, format, summary })
+}
+
+fn parse_rooms<I: Iterator<Item = String>>(mut args: I) -> Result<Command, UsageError> {
+    let mut inputs = InputFlags::new();
+
+    while let Some(arg) = args.next() {
+        if !inputs.parse(&arg, &mut args)? {
+            return error(format!("unexpected argument '{}'", arg));
+        }
+    }
+
+    let InputFlags { input, profile } = inputs;
+
+    Ok(Command::Rooms { input, profile })
+}
+
+fn parse_crossings<I: Iterator<Item = String>>(mut args: I) -> Result<Command, UsageError> {
+    let mut moves = 20;
+    let mut length = 1_000_000;
+    let mut runs = 3;
+    let mut engines = segments::Engine::ALL.to_vec();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--moves" => {
+                let value = flag_value(&arg, &mut args)?;
+                moves = parse_number(&arg, &value)? as usize;
+            },
+            "--length" => {
+                let value = flag_value(&arg, &mut args)?;
+                length = parse_number(&arg, &value)?;
+            },
+            "--runs" => runs = parse_runs(&flag_value(&arg, &mut args)?)?,
+            "--engine" => engines = vec![parse_engine::<segments::Engine>(&arg, &flag_value(&arg, &mut args)?)?],
+            _ => return error(format!("unexpected argument '{}'", arg)),
+        }
+    }
+
+    // keeps every coordinate of the spiral well inside an i32
+    if length > 100_000_000 || moves > 1_000_000 {
+        return error("--length is limited to 100000000 and --moves to 1000000".to_string());
+    }
+
+    Ok(Command::Crossings { moves, length, runs, engines })
+}
+
+fn parse_validate<I: Iterator<Item = String>>(mut args: I) -> Result<Command, UsageError> {
+    let mut rows = 3_000_000;
+    let mut part = None;
+    let mut grouping = None;
+    let mut runs = 3;
+    let mut engines = triangle_batch::Engine::ALL.to_vec();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--rows" => {
+                let value = flag_value(&arg, &mut args)?;
+                rows = parse_number(&arg, &value)? as usize;
+            },
+            "--part" => {
+                let value = flag_value(&arg, &mut args)?;
+                part = Some(parse_part(&arg, &value)?);
+            },
+            "--grouping" => grouping = Some(parse_grouping(&arg, &flag_value(&arg, &mut args)?)?),
+            "--runs" => runs = parse_runs(&flag_value(&arg, &mut args)?)?,
+            "--engine" => engines = vec![parse_engine::<triangle_batch::Engine>(&arg, &flag_value(&arg, &mut args)?)?],
+            _ => return error(format!("unexpected argument '{}'", arg)),
+        }
+    }
+
+    let groupings = match part_or_grouping(part, grouping)? {
+        Some(grouping) => vec![grouping],
+        None => vec![Grouping::Rows, Grouping::Columns(3)],
+    };
+
+    Ok(Command::Validate { rows, groupings, runs, engines })
+}
+
+fn parse_bench<I: Iterator<Item = String>>(mut args: I) -> Result<Command, UsageError> {
+    let mut day = None;
+    let mut part = None;
+    let mut runs = 10;
+    let mut format = Format::Table;
+    let mut profile = None;
+    let mut all_profiles = false;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--profile" => profile = Some(parse_profile(&arg, &flag_value(&arg, &mut args)?)?),
+            "--all-profiles" => all_profiles = true,
+            "--day" => {
+                let value = flag_value(&arg, &mut args)?;
+                day = Some(parse_number(&arg, &value)?);
+            },
+            "--part" => {
+                let value = flag_value(&arg, &mut args)?;
+                part = Some(parse_part(&arg, &value)?);
+            },
+            "--runs" => runs = parse_runs(&flag_value(&arg, &mut args)?)?,
+            "--format" => {
+                let value = flag_value(&arg, &mut args)?;
+                format = match Format::from_name(&value) {
+                    Some(f) => f,
+                    None => return error(format!("--format must be table, json or csv, got '{}'", value)),
+                };
+            },
+            _ => return error(format!("unexpected argument '{}'", arg)),
+        }
+    }
+
+    let profiles = select_profiles(profile, all_profiles)?;
+
+    Ok(Command::Bench { day, part, runs, format, profiles })
+}
+
+pub fn parse<I: Iterator<Item = String>>(mut args: I) -> Result<Command, UsageError> {
+    match args.next() {
+        Some(ref cmd) if cmd == "run" => parse_run(args),
+        Some(ref cmd) if cmd == "verify" => parse_verify(args),
+        Some(ref cmd) if cmd == "path" => parse_path(args),
+        Some(ref cmd) if cmd == "walk" => parse_walk(args),
+        Some(ref cmd) if cmd == "keypad" => parse_keypad(args),
+        Some(ref cmd) if cmd == "triangles" => parse_triangles(args),
+        Some(ref cmd) if cmd == "rooms" => parse_rooms(args),
+        Some(ref cmd) if cmd == "crossings" => parse_crossings(args),
+        Some(ref cmd) if cmd == "validate" => parse_validate(args),
+        Some(ref cmd) if cmd == "bench" => parse_bench(args),
+        Some(cmd) => error(format!("unknown command '{}'", cmd)),
+        None => error("no command given".to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_args(args: &[&str]) -> Result<Command, UsageError> {
+        parse(args.iter().map(|a| a.to_string()))
+    }
+
+    fn rejects(args: &[&str]) -> String {
+        match parse_args(args) {
+            Err(e) => e.0,
+            Ok(_) => panic!("{:?} should have been rejected", args),
+        }
+    }
+
+    #[test]
+    fn commands() {
+        assert_eq!(rejects(&[]), "no command given");
+        assert_eq!(rejects(&["solve"]), "unknown command 'solve'");
+        assert_eq!(rejects(&["run", "--day", "1", "--verbose"]), "unexpected argument '--verbose'");
+        assert_eq!(rejects(&["rooms", "--day", "4"]), "unexpected argument '--day'");
+    }
+
+    #[test]
+    fn flag_values() {
+        assert_eq!(rejects(&["run", "--day"]), "--day requires a value");
+        assert_eq!(rejects(&["run", "--day", "x"]), "--day expects a number, got 'x'");
+        assert_eq!(rejects(&["run", "--day", "1", "--part", "3"]), "--part must be 1 or 2, got '3'");
+        assert_eq!(rejects(&["run", "--day", "1", "--profile", "../x"]), "--profile '../x' is not a valid profile name");
+        assert_eq!(rejects(&["keypad", "--start", "12"]), "--start expects a single key label, got '12'");
+        assert_eq!(rejects(&["path", "--map", "--size", "80"]), "--size expects a size like 80x24, got '80'");
+        assert_eq!(rejects(&["walk", "--grammar", "logo"]), "--grammar must be compass or turtle, got 'logo'");
+        assert_eq!(rejects(&["triangles", "--grouping", "columns:0"]),
+            "--grouping must be rows, columns, columns:<N> or transposed, got 'columns:0'");
+        assert_eq!(rejects(&["crossings", "--engine", "scan"]), "--engine must be walk, pairwise or sweep, got 'scan'");
+        assert_eq!(rejects(&["validate", "--engine", "scan"]), "--engine must be lines or batch, got 'scan'");
+        assert_eq!(rejects(&["bench", "--runs", "0"]), "--runs must be at least 1");
+        assert_eq!(rejects(&["crossings", "--runs", "x"]), "--runs expects a number, got 'x'");
+    }
+
+    #[test]
+    fn input_flags() {
+        match parse_args(&["run", "--day", "1", "--profile", "alice", "--input-text", "R2"]).ok() {
+            Some(Command::Run { day: 1, part: None, input: Some(Source::Inline(ref text)), ref profile }) =>
+                assert_eq!((text.as_str(), profile.name()), ("R2", Some("alice"))),
+            _ => panic!("expected run with inline input"),
+        }
+        assert!(matches!(parse_args(&["rooms", "--input", "-"]),
+            Ok(Command::Rooms { input: Some(Source::Stdin), .. })));
+        assert!(matches!(parse_args(&["triangles", "--input", "in.txt"]),
+            Ok(Command::Triangles { input: Some(Source::Path(_)), .. })));
+    }
+
+    #[test]
+    fn flag_combinations() {
+        assert_eq!(rejects(&["run"]), "run requires --day or --all");
+        assert_eq!(rejects(&["run", "--all", "--day", "1"]), "--all and --day cannot be combined");
+        assert_eq!(rejects(&["run", "--all", "--input", "x"]), "--all always uses each day's own input");
+        assert_eq!(rejects(&["verify", "--profile", "alice", "--all-profiles"]), "--profile and --all-profiles cannot be combined");
+        assert_eq!(rejects(&["verify", "--answers", "a.toml", "--all-profiles"]), "--answers cannot be combined with --all-profiles");
+        assert_eq!(rejects(&["bench", "--profile", "alice", "--all-profiles"]), "--profile and --all-profiles cannot be combined");
+        assert_eq!(rejects(&["path"]), "path requires at least one of --logo, --svg, --csv, --stats or --map");
+        assert_eq!(rejects(&["path", "--stats", "--size", "80x24"]), "--size only applies to --map");
+        assert_eq!(rejects(&["triangles", "--summary", "--format", "csv"]), "--summary is only printed as a table");
+        assert_eq!(rejects(&["triangles", "--part", "1", "--grouping", "rows"]), "only one of --part and --grouping can be given");
+        assert_eq!(rejects(&["validate", "--part", "2", "--grouping", "columns"]), "only one of --part and --grouping can be given");
+        assert_eq!(rejects(&["crossings", "--length", "100000001"]), "--length is limited to 100000000 and --moves to 1000000");
+    }
+
+    #[test]
+    fn keypad_modes() {
+        assert_eq!(rejects(&["keypad", "--code", "5"]), "--code only applies to --reach");
+        assert_eq!(rejects(&["keypad", "--delay", "5"]), "--delay only applies to --trace animate");
+        assert_eq!(rejects(&["keypad", "--trace", "heatmap", "--delay", "5"]), "--delay only applies to --trace animate");
+        assert_eq!(rejects(&["keypad", "--trace", "replay"]), "--trace must be animate or heatmap, got 'replay'");
+        assert_eq!(rejects(&["keypad", "--reach", "--stream"]), "only one of --reach, --for-code, --trace and --stream can be given");
+        assert_eq!(rejects(&["keypad", "--for-code", "5", "--input-text", "U"]), "--reach and --for-code don't read any input");
+        assert_eq!(rejects(&["keypad", "--stream"]), "--stream requires --input or --input-text");
+
+        assert!(matches!(parse_args(&["keypad"]), Ok(Command::Keypad { mode: KeypadMode::Code, .. })));
+        assert!(matches!(parse_args(&["keypad", "--trace", "animate", "--delay", "50"]),
+            Ok(Command::Keypad { mode: KeypadMode::Trace(Style::Animate { delay_ms: 50 }), .. })));
+        assert!(matches!(parse_args(&["keypad", "--trace", "animate"]),
+            Ok(Command::Keypad { mode: KeypadMode::Trace(Style::Animate { delay_ms: 150 }), .. })));
+    }
+
+    #[test]
+    fn defaults() {
+        match parse_args(&["triangles", "--part", "2"]).ok() {
+            Some(Command::Triangles { grouping, summary, .. }) => assert_eq!((grouping, summary), (Grouping::Columns(3), false)),
+            _ => panic!("expected triangles"),
+        }
+        match parse_args(&["validate"]).ok() {
+            Some(Command::Validate { rows, ref groupings, runs, ref engines }) => {
+                assert_eq!((rows, runs), (3_000_000, 3));
+                assert_eq!(*groupings, vec![Grouping::Rows, Grouping::Columns(3)]);
+                assert_eq!(*engines, triangle_batch::Engine::ALL.to_vec());
+            },
+            _ => panic!("expected validate"),
+        }
+        match parse_args(&["crossings", "--engine", "sweep"]).ok() {
+            Some(Command::Crossings { ref engines, .. }) => assert_eq!(*engines, vec![segments::Engine::Sweep]),
+            _ => panic!("expected crossings"),
+        }
+    }
+}
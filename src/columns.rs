@@ -0,0 +1,197 @@
+use std::collections::VecDeque;
+use std::fmt;
+use std::iter::Fuse;
+
+// Ways of reading n-tuples out of the rows of an n-column table. Each is a
+// block height: a block of rows is read down its first column, then its
+// second and so on, and cut into n-tuples. With n = 2:
+//
+//              rows    columns(2)    transposed
+//     1 2      1 2     1 3           1 3
+//     3 4      3 4     2 4           5 2
+//     5 6      5 6                   4 6
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Grouping {
+    Rows,
+    Columns(usize),
+    // the whole table is one block, so it's all held in memory
+    Transposed,
+}
+
+impl Grouping {
+    // rows, columns (three rows at a time, day 3's part 2), columns:<N> or transposed
+    pub fn from_name(name: &str) -> Option<Grouping> {
+        match name {
+            "rows" => Some(Grouping::Rows),
+            "columns" => Some(Grouping::Columns(3)),
+            "transposed" => Some(Grouping::Transposed),
+            _ => match name.strip_prefix("columns:").map(str::parse) {
+                Some(Ok(height)) if height > 0 => Some(Grouping::Columns(height)),
+                _ => None,
+            },
+        }
+    }
+
+    // None for the whole table
+    pub fn height(self) -> Option<usize> {
+        match self {
+            Grouping::Rows => Some(1),
+            Grouping::Columns(height) => Some(height),
+            Grouping::Transposed => None,
+        }
+    }
+}
+
+impl fmt::Display for Grouping {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match *self {
+            Grouping::Rows => "rows".to_string(),
+            Grouping::Columns(height) => format!("columns:{}", height),
+            Grouping::Transposed => "transposed".to_string(),
+        };
+        f.pad(&name)
+    }
+}
+
+// Why a row can't be regrouped, passed to the caller's error function
+// along with the row's index
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Shape {
+    // the row has this many columns rather than n
+    Width(usize),
+    // the rows from this one on are too few to fill a block
+    Leftover,
+}
+
+// Regroups the rows of an n-column table into n-tuples as above. Only one
+// block of rows is held at once. Errors pass straight through, and a row of
+// the wrong width or a final block of fewer rows than the rest becomes an
+// error from `error`.
+pub struct Grouped<I, T, F> {
+    rows: Fuse<I>,
+    n: usize,
+    height: Option<usize>,
+    error: F,
+    read: usize,
+    ready: VecDeque<Vec<T>>,
+}
+
+impl<I, T, E, F> Iterator for Grouped<I, T, F>
+    where I: Iterator<Item = Result<Vec<T>, E>>, T: Clone, F: FnMut(usize, Shape) -> E {
+    type Item = Result<Vec<T>, E>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.ready.is_empty() {
+            let mut block = Vec::with_capacity(self.height.unwrap_or(0));
+            while self.height.is_none_or(|height| block.len() < height) {
+                let row = self.rows.next();
+                self.read += 1;
+                match row {
+                    Some(Ok(ref row)) if row.len() != self.n =>
+                        return Some(Err((self.error)(self.read - 1, Shape::Width(row.len())))),
+                    Some(Ok(row)) => block.push(row),
+                    Some(Err(e)) => return Some(Err(e)),
+                    None if self.height.is_none() || block.is_empty() => break,
+                    None => {
+                        let first = self.read - 1 - block.len();
+                        return Some(Err((self.error)(first, Shape::Leftover)));
+                    },
+                }
+            }
+            let mut down = (0..self.n).flat_map(|col| block.iter().map(move |row| row[col].clone()));
+            while let Some(first) = down.next() {
+                let mut tuple = vec![first];
+                tuple.extend(down.by_ref().take(self.n - 1));
+                self.ready.push_back(tuple);
+            }
+        }
+        self.ready.pop_front().map(Ok)
+    }
+}
+
+pub trait GroupedExt<T, E>: Sized {
+    fn grouped<F>(self, n: usize, grouping: Grouping, error: F) -> Grouped<Self, T, F>
+        where F: FnMut(usize, Shape) -> E;
+}
+
+impl<I, T, E> GroupedExt<T, E> for I where I: Iterator<Item = Result<Vec<T>, E>> {
+    fn grouped<F>(self, n: usize, grouping: Grouping, error: F) -> Grouped<Self, T, F>
+        where F: FnMut(usize, Shape) -> E {
+        Grouped { rows: self.fuse(), n, height: grouping.height(), error, read: 0, ready: VecDeque::new() }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table(rows: &[&[u32]]) -> Vec<Result<Vec<u32>, String>> {
+        rows.iter().map(|r| Ok(r.to_vec())).collect()
+    }
+
+    fn shape(row: usize, shape: Shape) -> String {
+        format!("row {}: {:?}", row, shape)
+    }
+
+    fn grouped(rows: &[&[u32]], grouping: Grouping) -> Vec<Result<Vec<u32>, String>> {
+        table(rows).into_iter().grouped(rows[0].len(), grouping, shape).collect()
+    }
+
+    #[test]
+    fn regroups_columns() {
+        let pairs = grouped(&[&[1, 2], &[3, 4], &[5, 6], &[7, 8]], Grouping::Columns(2));
+        assert_eq!(pairs, table(&[&[1, 3], &[2, 4], &[5, 7], &[6, 8]]));
+
+        let rows: &[&[u32]] = &[&[1, 2, 3, 4], &[5, 6, 7, 8], &[9, 10, 11, 12], &[13, 14, 15, 16]];
+        assert_eq!(grouped(rows, Grouping::Columns(4)),
+            table(&[&[1, 5, 9, 13], &[2, 6, 10, 14], &[3, 7, 11, 15], &[4, 8, 12, 16]]));
+    }
+
+    #[test]
+    fn any_block_height() {
+        let rows: &[&[u32]] = &[&[1, 2], &[3, 4], &[5, 6], &[7, 8], &[9, 10]];
+        assert_eq!(grouped(rows, Grouping::Rows), table(rows));
+        assert_eq!(grouped(rows, Grouping::Columns(1)), table(rows));
+        assert_eq!(grouped(rows, Grouping::Columns(5)),
+            table(&[&[1, 3], &[5, 7], &[9, 2], &[4, 6], &[8, 10]]));
+        assert_eq!(grouped(rows, Grouping::Transposed), grouped(rows, Grouping::Columns(5)));
+        assert_eq!(grouped(&rows[..3], Grouping::Transposed), table(&[&[1, 3], &[5, 2], &[4, 6]]));
+    }
+
+    #[test]
+    fn leftover_rows_are_errors() {
+        let rows: &[&[u32]] = &[&[1, 2], &[3, 4], &[5, 6], &[7, 8], &[9, 10]];
+        let mut blocks = table(rows).into_iter().grouped(2, Grouping::Columns(3), shape);
+        assert_eq!(blocks.by_ref().take(3).collect::<Vec<_>>(), table(&[&[1, 3], &[5, 2], &[4, 6]]));
+        assert_eq!(blocks.next(), Some(Err("row 3: Leftover".to_string())));
+        assert_eq!(blocks.next(), None);
+    }
+
+    #[test]
+    fn passes_errors_through() {
+        let mut rows = table(&[&[1, 2], &[3, 4], &[5, 6]]);
+        rows.insert(2, Err("bad row".to_string()));
+        let mut pairs = rows.into_iter().grouped(2, Grouping::Columns(2), shape);
+        assert_eq!(pairs.next(), Some(Ok(vec![1, 3])));
+        assert_eq!(pairs.next(), Some(Ok(vec![2, 4])));
+        assert_eq!(pairs.next(), Some(Err("bad row".to_string())));
+    }
+
+    #[test]
+    fn ragged_rows_are_errors() {
+        let rows: &[&[u32]] = &[&[1, 2], &[3, 4], &[5], &[7, 8]];
+        assert_eq!(grouped(rows, Grouping::Rows), vec![
+            Ok(vec![1, 2]), Ok(vec![3, 4]), Err("row 2: Width(1)".to_string()), Ok(vec![7, 8]),
+        ]);
+        assert_eq!(grouped(rows, Grouping::Transposed)[0], Err("row 2: Width(1)".to_string()));
+    }
+
+    #[test]
+    fn grouping_names() {
+        assert_eq!(Grouping::from_name("columns"), Some(Grouping::Columns(3)));
+        assert_eq!(Grouping::from_name("columns:12"), Some(Grouping::Columns(12)));
+        assert_eq!(Grouping::from_name("columns:0"), None);
+        assert_eq!(Grouping::Columns(12).to_string(), "columns:12");
+        assert_eq!(Grouping::from_name("transposed"), Some(Grouping::Transposed));
+    }
+}
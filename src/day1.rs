@@ -1,7 +1,10 @@
-use get_input::get_input;
+use std::collections::HashSet;
+
+use parse_error::ParseError;
+use solver::{Answer, Solver};
 
 #[derive(Copy, Clone)]
-enum Rotation {
+pub enum Rotation {
     Left,
     Right,
 }
@@ -39,13 +42,20 @@ impl Direction {
     }
 }
 
-struct Position {
-    x: i32,
-    y: i32,
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+// i64 so no number of i32 moves, each up to i32::MAX blocks, can overflow
+// in practice
+pub struct Position {
+    pub x: i64,
+    pub y: i64,
 }
 
 impl Position {
-    fn move_by(self, dir: Direction, dist: i32) -> Position {
+    pub fn distance(self) -> i64 {
+        self.x.abs() + self.y.abs()
+    }
+
+    fn move_by(self, dir: Direction, dist: i64) -> Position {
         match dir {
             Direction::North => Position { x: self.x, y: self.y + dist },
             Direction::East => Position { x: self.x + dist, y: self.y },
@@ -55,47 +65,116 @@ impl Position {
     }
 }
 
-fn parse_movement(m: &str) -> (Rotation, i32) {
-    let mut m = m.trim().chars();
-    let rotation = m.next().expect("Could not read input");
-    let rotation = Rotation::from_char(rotation).unwrap();
-    let distance = m.as_str().parse::<i32>().expect("Could not read integer in input");
-    (rotation, distance)
+fn parse_movement(input: &str, m: &str) -> Result<(Rotation, i32), ParseError> {
+    let m = m.trim();
+    let mut chars = m.chars();
+    let rotation = match chars.next() {
+        Some(c) => c,
+        None => return Err(ParseError::at(input, m, "a movement like 'R2'")),
+    };
+    let rotation = Rotation::from_char(rotation)
+        .ok_or_else(|| ParseError::at(input, &m[..rotation.len_utf8()], "'L' or 'R'"))?;
+    let distance = chars.as_str();
+    match distance.parse::<i32>() {
+        Ok(d) if d >= 0 => Ok((rotation, d)),
+        _ => Err(ParseError::at(input, distance, "a distance")),
+    }
 }
 
-pub fn solve() {
-    let input = get_input("assets/day/1/input");
-    //let input = "R8, R4, R4, R8";
+pub fn parse_movements(input: &str) -> Result<Vec<(Rotation, i32)>, ParseError> {
+    input.split(',').map(|m| parse_movement(input, m)).collect()
+}
+
+// Start plus the position after every movement
+pub fn corners(movements: &[(Rotation, i32)]) -> Vec<Position> {
     let mut position = Position { x: 0, y: 0 };
     let mut direction = Direction::North;
+    let mut corners = vec![position];
 
-    for movement in input.split(',') {
-        let (rotation, distance) = parse_movement(&movement);
+    for &(rotation, distance) in movements {
         direction = direction.rotate(rotation);
-        position = position.move_by(direction, distance);
-
-        // Print Logo commands to draw the path,
-        // turned out the first repeated position was easy to visually spot
-        // 75, -4, for a distance of 79 for part 2
-        println!("{} 90 fd {} -- {}, {}",
-             match rotation {
-                 Rotation::Left => "lt",
-                 Rotation::Right => "rt",
-             },
-             distance,
-             position.x,
-             position.y
-         );
-
-        /*
-        println!("{} x: {}, y: {}, facing {}",
-            movement.trim(),
-            position.x,
-            position.y,
-            pretty_orientation(orientation),
-        );
-        */
+        position = position.move_by(direction, distance as i64);
+        corners.push(position);
     }
 
-    println!("Distance: {}", position.x.abs() + position.y.abs());
+    corners
+}
+
+// Walks the path one block at a time, so every cell passed through counts,
+// not just the corners
+pub fn first_revisit(movements: &[(Rotation, i32)]) -> Option<Position> {
+    let mut position = Position { x: 0, y: 0 };
+    let mut direction = Direction::North;
+    let mut visited = HashSet::new();
+    visited.insert(position);
+
+    for &(rotation, distance) in movements {
+        direction = direction.rotate(rotation);
+        for _ in 0..distance {
+            position = position.move_by(direction, 1);
+            if !visited.insert(position) {
+                return Some(position);
+            }
+        }
+    }
+
+    None
+}
+
+pub struct Day1;
+
+impl Solver for Day1 {
+    fn part1(&self, input: &str) -> Result<Answer, ParseError> {
+        let corners = corners(&parse_movements(input)?);
+        Ok(corners[corners.len() - 1].distance().into())
+    }
+
+    fn part2(&self, input: &str) -> Result<Answer, ParseError> {
+        first_revisit(&parse_movements(input)?)
+            .map(|position| position.distance().into())
+            .ok_or_else(|| ParseError::at(input, &input[input.len()..], "a location visited twice"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn part1_examples() {
+        assert_eq!(Day1.part1("R2, L3").unwrap(), Answer::Integer(5));
+        assert_eq!(Day1.part1("R2, R2, R2").unwrap(), Answer::Integer(2));
+        assert_eq!(Day1.part1("R5, L5, R5, R3").unwrap(), Answer::Integer(12));
+    }
+
+    #[test]
+    fn part2_example() {
+        let movements = parse_movements("R8, R4, R4, R8").unwrap();
+        assert_eq!(first_revisit(&movements), Some(Position { x: 4, y: 0 }));
+        assert_eq!(Day1.part2("R8, R4, R4, R8").unwrap(), Answer::Integer(4));
+    }
+
+    #[test]
+    fn part2_counts_cells_between_corners() {
+        // crosses its own first leg at 0, 2 without ever landing on a corner twice
+        assert_eq!(Day1.part2("R0, L4, L2, L2, L4").unwrap(), Answer::Integer(2));
+        let e = Day1.part2("R2, L3").unwrap_err();
+        assert_eq!((e.line, e.column, e.expected.as_str()), (1, 7, "a location visited twice"));
+    }
+
+    #[test]
+    fn huge_moves() {
+        assert_eq!(Day1.part1("R2147483647, L0, R1").unwrap(), Answer::Integer(2147483648));
+        let corners = corners(&parse_movements("R2147483647, L2147483647, L2147483647, L2147483647").unwrap());
+        assert_eq!(corners[2], Position { x: 2147483647, y: 2147483647 });
+        assert_eq!(corners[2].distance(), 4294967294);
+    }
+
+    #[test]
+    fn bad_movement() {
+        let e = Day1.part1("R5, Lx").unwrap_err();
+        assert_eq!((e.line, e.column, e.token.as_str()), (1, 6, "x"));
+        let e = Day1.part1("R5, L-3").unwrap_err();
+        assert_eq!((e.line, e.column, e.token.as_str()), (1, 6, "-3"));
+    }
 }
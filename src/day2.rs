@@ -1,46 +1,242 @@
-use std::str::Chars;
+use std::collections::{HashMap, VecDeque};
+use std::error;
 use std::fmt;
+use std::io::{self, BufRead};
 
-use get_input::get_input;
+use parse_error::ParseError;
+use solver::{Answer, Solver};
 
-const IS_PART1_KEYPAD: bool = false;
+// Keypads are drawn as a grid of key labels, a space where there's no key
+pub const SQUARE: &str = "123\n456\n789";
+pub const DIAMOND: &str = "  1\n 234\n56789\n ABC\n  D";
 
-#[derive(Copy, Clone)]
-enum Key {
-    One,
-    Two,
-    Three,
-    Four,
-    Five,
-    Six,
-    Seven,
-    Eight,
-    Nine,
-    A,  // A, B, C, D for Part 2
-    B,
-    C,
-    D,
+// A key's place in the layout grid
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Key {
+    row: usize,
+    col: usize,
 }
 
-impl fmt::Display for Key {
+#[derive(Debug, PartialEq)]
+pub enum LayoutError {
+    Empty,
+    // 1-based line and column of both copies
+    DuplicateKey { label: char, first: (usize, usize), second: (usize, usize) },
+    NoStartKey(char),
+}
+
+impl fmt::Display for LayoutError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            LayoutError::Empty => write!(f, "layout has no keys"),
+            LayoutError::DuplicateKey { label, first, second } =>
+                write!(f, "key '{}' appears twice, at line {}, column {} and line {}, column {}",
+                    label, first.0, first.1, second.0, second.1),
+            LayoutError::NoStartKey(label) => write!(f, "layout has no start key '{}'", label),
+        }
+    }
+}
+
+impl error::Error for LayoutError {}
+
+#[derive(Debug, PartialEq)]
+pub enum CodeError {
+    NoKey(char),
+    Unreachable(char),
+}
+
+impl fmt::Display for CodeError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{}", match *self {
-            Key::One => '1', Key::Two => '2', Key::Three => '3',
-            Key::Four => '4', Key::Five => '5', Key::Six => '6',
-            Key::Seven => '7', Key::Eight => '8', Key::Nine => '9',
-            Key::A => 'A', Key::B => 'B', Key::C => 'C', Key::D => 'D',
-        })
+        match *self {
+            CodeError::NoKey(label) => write!(f, "there is no key '{}'", label),
+            CodeError::Unreachable(label) => write!(f, "key '{}' can't be reached", label),
+        }
     }
 }
 
-enum Direction {
+impl error::Error for CodeError {}
+
+#[derive(Debug)]
+pub enum StreamError {
+    Io(io::Error),
+    // 1-based, counting bytes
+    BadDirection { line: usize, column: usize, found: u8 },
+}
+
+impl fmt::Display for StreamError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            StreamError::Io(ref e) => write!(f, "{}", e),
+            StreamError::BadDirection { line, column, found } if found.is_ascii_graphic() =>
+                write!(f, "line {}, column {}: expected one of 'U', 'D', 'L' or 'R', found '{}'",
+                    line, column, found as char),
+            StreamError::BadDirection { line, column, found } =>
+                write!(f, "line {}, column {}: expected one of 'U', 'D', 'L' or 'R', found byte 0x{:02x}",
+                    line, column, found),
+        }
+    }
+}
+
+impl error::Error for StreamError {}
+
+impl From<io::Error> for StreamError {
+    fn from(e: io::Error) -> StreamError {
+        StreamError::Io(e)
+    }
+}
+
+pub struct Keypad {
+    grid: Vec<Vec<Option<char>>>,
+    start: Key,
+}
+
+impl Keypad {
+    pub fn new(layout: &str, start: char) -> Result<Keypad, LayoutError> {
+        let grid: Vec<Vec<Option<char>>> = layout.lines()
+            .map(|line| line.chars().map(|c| if c == ' ' { None } else { Some(c) }).collect())
+            .collect();
+
+        let mut seen: HashMap<char, (usize, usize)> = HashMap::new();
+        for (row, r) in grid.iter().enumerate() {
+            for (col, &k) in r.iter().enumerate() {
+                if let Some(label) = k {
+                    if let Some(&first) = seen.get(&label) {
+                        return Err(LayoutError::DuplicateKey {
+                            label,
+                            first: (first.0 + 1, first.1 + 1),
+                            second: (row + 1, col + 1),
+                        });
+                    }
+                    seen.insert(label, (row, col));
+                }
+            }
+        }
+
+        if seen.is_empty() {
+            return Err(LayoutError::Empty);
+        }
+        match seen.get(&start) {
+            Some(&(row, col)) => Ok(Keypad { grid, start: Key { row, col } }),
+            None => Err(LayoutError::NoStartKey(start)),
+        }
+    }
+
+    // Every key, reading the layout left to right, top to bottom
+    pub fn keys(&self) -> Vec<Key> {
+        self.grid.iter().enumerate()
+            .flat_map(|(row, r)| r.iter().enumerate().filter(|&(_, k)| k.is_some()).map(move |(col, _)| Key { row, col }))
+            .collect()
+    }
+
+    pub fn start(&self) -> Key {
+        self.start
+    }
+
+    fn at(&self, row: usize, col: usize) -> Option<char> {
+        self.grid.get(row).and_then(|r| r.get(col)).and_then(|&k| k)
+    }
+
+    pub fn find(&self, label: char) -> Option<Key> {
+        self.keys().into_iter().find(|&k| self.label(k) == label)
+    }
+
+    pub fn label(&self, key: Key) -> char {
+        self.at(key.row, key.col).unwrap()
+    }
+
+    // Moving off the edge of the keypad, or into a gap, stays put
+    pub fn step(&self, key: Key, d: Direction) -> Key {
+        let (row, col) = match d {
+            Direction::Up => (key.row.wrapping_sub(1), key.col),
+            Direction::Down => (key.row + 1, key.col),
+            Direction::Left => (key.row, key.col.wrapping_sub(1)),
+            Direction::Right => (key.row, key.col + 1),
+        };
+        match self.at(row, col) {
+            Some(_) => Key { row, col },
+            None => key,
+        }
+    }
+
+    // The layout redrawn with `cell` filling in each key, centred in
+    // columns wide enough for the longest
+    pub fn draw<F: Fn(Key) -> String>(&self, cell: F) -> Vec<String> {
+        let cells: HashMap<Key, String> = self.keys().into_iter().map(|k| (k, cell(k))).collect();
+        let width = cells.values().map(|c| c.chars().count()).max().unwrap_or(1);
+
+        self.grid.iter().enumerate().map(|(row, r)| {
+            let line: String = (0..r.len()).map(|col| match cells.get(&Key { row, col }) {
+                Some(c) => format!(" {:^width$} ", c, width = width),
+                None => " ".repeat(width + 2),
+            }).collect();
+            line.trim_end().to_string()
+        }).collect()
+    }
+
+    // Breadth first, so every route is as short as it gets; ties go to
+    // whichever of U, D, L, R comes first
+    fn routes_from(&self, from: Key) -> HashMap<Key, String> {
+        let mut routes = HashMap::new();
+        let mut queue = VecDeque::new();
+        routes.insert(from, String::new());
+        queue.push_back(from);
+
+        while let Some(key) = queue.pop_front() {
+            for &d in DIRECTIONS {
+                let next = self.step(key, d);
+                if !routes.contains_key(&next) {
+                    let route = format!("{}{}", routes[&key], d.letter());
+                    routes.insert(next, route);
+                    queue.push_back(next);
+                }
+            }
+        }
+        routes
+    }
+
+    pub fn reachable(&self) -> Vec<Key> {
+        let routes = self.routes_from(self.start);
+        self.keys().into_iter().filter(|k| routes.contains_key(k)).collect()
+    }
+
+    pub fn route(&self, from: Key, to: Key) -> Option<String> {
+        self.routes_from(from).remove(&to)
+    }
+
+    // One shortest line per key of the code, each starting where the last left off
+    pub fn type_code(&self, code: &str) -> Result<Vec<String>, CodeError> {
+        let mut key = self.start;
+        let mut lines = Vec::new();
+
+        for label in code.chars() {
+            let target = self.find(label).ok_or(CodeError::NoKey(label))?;
+            lines.push(self.route(key, target).ok_or(CodeError::Unreachable(label))?);
+            key = target;
+        }
+        Ok(lines)
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Direction {
     Up,
     Down,
     Left,
     Right,
 }
 
+const DIRECTIONS: &[Direction] = &[Direction::Up, Direction::Down, Direction::Left, Direction::Right];
+
 impl Direction {
+    pub fn letter(self) -> char {
+        match self {
+            Direction::Up => 'U',
+            Direction::Down => 'D',
+            Direction::Left => 'L',
+            Direction::Right => 'R',
+        }
+    }
+
     fn from_char(c: char) -> Option<Direction> {
         match c {
             'U' => Some(Direction::Up),
@@ -52,85 +248,269 @@ impl Direction {
     }
 }
 
-fn move_one_key(k: Key, d: Direction) -> Key {
-    if IS_PART1_KEYPAD {
-        match d {
-            Direction::Up => match k {
-                Key::One | Key::Two | Key::Three => k,
-                Key::Four => Key::One, Key::Five => Key::Two, Key::Six => Key::Three,
-                Key::Seven => Key::Four, Key::Eight => Key::Five, Key::Nine => Key::Six,
-                _ => unreachable!(),
-            },
-            Direction::Down => match k {
-                Key::One => Key::Four, Key::Two => Key::Five, Key::Three => Key::Six,
-                Key::Four => Key::Seven, Key::Five => Key::Eight, Key::Six => Key::Nine,
-                Key::Seven | Key::Eight | Key::Nine => k,
-                _ => unreachable!(),
-            },
-            Direction::Left => match k {
-                Key::One | Key::Four | Key::Seven => k,
-                Key::Two => Key::One, Key::Five => Key::Four, Key::Eight => Key::Seven,
-                Key::Three => Key::Two, Key::Six => Key::Five, Key::Nine => Key::Eight,
-                _ => unreachable!(),
-            },
-            Direction::Right => match k {
-                Key::One => Key::Two, Key::Four => Key::Five, Key::Seven => Key::Eight,
-                Key::Two => Key::Three, Key::Five => Key::Six, Key::Eight => Key::Nine,
-                Key::Three | Key::Six | Key::Nine => k,
-                _ => unreachable!(),
-            },
-        }
+// One move of the finger; a blocked move leaves it on the same key
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Move {
+    pub direction: Direction,
+    pub key: Key,
+    pub blocked: bool,
+}
+
+fn compute_next_key<F>(keypad: &Keypad, k: Key, input: &str, line: &str, mut on_move: F)
+    -> Result<Key, ParseError> where F: FnMut(Move) {
+    let mut current_key = k;
+    for (i, c) in line.char_indices() {
+        let dir = Direction::from_char(c).ok_or_else(|| {
+            ParseError::at(input, &line[i..i + c.len_utf8()], "one of 'U', 'D', 'L' or 'R'")
+        })?;
+        let next_key = keypad.step(current_key, dir);
+        on_move(Move { direction: dir, key: next_key, blocked: next_key == current_key });
+        current_key = next_key;
     }
-    else {
-        match d {
-            Direction::Up => match k {
-                Key::Five | Key::Two | Key::One | Key::Four | Key::Nine => k,
-                Key::Three => Key::One,
-                Key::Six => Key::Two, Key::Seven => Key::Three, Key::Eight => Key::Four,
-                Key::A => Key::Six, Key::B => Key::Seven, Key::C => Key::Eight,
-                Key::D => Key::B,
-            },
-            Direction::Down => match k {
-                Key::One => Key::Three,
-                Key::Two => Key::Six, Key::Three => Key::Seven, Key::Four => Key::Eight,
-                Key::Six => Key::A, Key::Seven => Key::B, Key::Eight => Key::C,
-                Key::B => Key::D,
-                Key::Five | Key::A | Key::D | Key::C | Key::Nine => k,
-            },
-            Direction::Left => match k {
-                Key::One | Key::Two | Key::Five | Key::A | Key::D => k,
-                Key::Six => Key::Five,
-                Key::Three => Key::Two, Key::Seven => Key::Six, Key::B => Key::A,
-                Key::Four => Key::Three, Key::Eight => Key::Seven, Key::C => Key::B,
-                Key::Nine => Key::Eight,
+    Ok(current_key)
+}
+
+pub fn door_code(keypad: &Keypad, input: &str) -> Result<String, ParseError> {
+    let mut current_key = keypad.start();
+    let mut code = String::new();
+
+    for line in input.lines() {
+        current_key = compute_next_key(keypad, current_key, input, line, |_| ())?;
+        code.push(keypad.label(current_key));
+    }
+    Ok(code)
+}
+
+// door_code a byte at a time, handing each key to `emit` as soon as its
+// line ends, so input of any size runs in constant memory
+pub fn stream_code<R, F>(keypad: &Keypad, reader: R, mut emit: F) -> Result<(), StreamError>
+    where R: BufRead, F: FnMut(char) -> io::Result<()> {
+    let mut key = keypad.start();
+    let (mut line, mut column) = (1, 0);
+    let mut in_line = false;
+    // a \r is only a line ending right before \n, as for str::lines
+    let mut cr = false;
+
+    for byte in reader.bytes() {
+        let byte = byte?;
+        column += 1;
+        if cr && byte != b'\n' {
+            return Err(StreamError::BadDirection { line, column: column - 1, found: b'\r' });
+        }
+        cr = false;
+        match byte {
+            b'\n' => {
+                emit(keypad.label(key))?;
+                line += 1;
+                column = 0;
+                in_line = false;
             },
-            Direction::Right => match k {
-                Key::Five => Key::Six,
-                Key::Two => Key::Three, Key::Six => Key::Seven, Key::A => Key::B,
-                Key::Three => Key::Four, Key::Seven => Key::Eight, Key::B => Key::C,
-                Key::Eight => Key::Nine,
-                Key::One | Key::Four | Key::Nine | Key::C | Key::D => k,
+            b'\r' => cr = true,
+            _ => {
+                let dir = Direction::from_char(byte as char)
+                    .ok_or(StreamError::BadDirection { line, column, found: byte })?;
+                key = keypad.step(key, dir);
+                in_line = true;
             },
         }
     }
-}
 
-fn compute_next_key(k: Key, mut chars: Chars) -> Key {
-    let mut current_key = k;
-    while let Some(c) = chars.next() {
-        let dir = Direction::from_char(c).expect("Invalid input");
-        current_key = move_one_key(current_key, dir);
+    if cr {
+        return Err(StreamError::BadDirection { line, column, found: b'\r' });
     }
-    current_key
+    // a last line without its newline still types a key
+    if in_line {
+        emit(keypad.label(key))?;
+    }
+    Ok(())
+}
+
+// Where each line starts and every move the finger makes along it
+pub struct LineTrace {
+    pub start: Key,
+    pub moves: Vec<Move>,
+    pub end: Key,
 }
 
-pub fn solve() {
-    let input = get_input("assets/day/2/input");
-    let mut current_key = Key::Five;
+pub fn trace(keypad: &Keypad, input: &str) -> Result<Vec<LineTrace>, ParseError> {
+    let mut current_key = keypad.start();
+    let mut traces = Vec::new();
 
     for line in input.lines() {
-        current_key = compute_next_key(current_key, line.chars());
-        print!("{}", current_key);
+        let mut moves = Vec::new();
+        let end = compute_next_key(keypad, current_key, input, line, |m| moves.push(m))?;
+        traces.push(LineTrace { start: current_key, moves, end });
+        current_key = end;
+    }
+    Ok(traces)
+}
+
+// The reverse of door_code: an input whose lines type exactly `code`,
+// each line as short as it can be
+pub fn instructions_for(keypad: &Keypad, code: &str) -> Result<String, CodeError> {
+    let mut input = String::new();
+    for line in keypad.type_code(code)? {
+        input.push_str(&line);
+        input.push('\n');
+    }
+    Ok(input)
+}
+
+pub struct Day2;
+
+impl Solver for Day2 {
+    fn part1(&self, input: &str) -> Result<Answer, ParseError> {
+        door_code(&Keypad::new(SQUARE, '5').unwrap(), input).map(Answer::from)
+    }
+
+    fn part2(&self, input: &str) -> Result<Answer, ParseError> {
+        door_code(&Keypad::new(DIAMOND, '5').unwrap(), input).map(Answer::from)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "ULL\nRRDDD\nLURDL\nUUUUD\n";
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(Day2.part1(EXAMPLE).unwrap(), Answer::Text("1985".to_string()));
+    }
+
+    #[test]
+    fn part2_example() {
+        assert_eq!(Day2.part2(EXAMPLE).unwrap(), Answer::Text("5DB3".to_string()));
+    }
+
+    #[test]
+    fn custom_layouts() {
+        let hex = Keypad::new("0123\n4567\n89AB\nCDEF", '5').unwrap();
+        assert_eq!(door_code(&hex, "RR\nDDD\nLLLU").unwrap(), "7F8");
+
+        let phone = Keypad::new("123\n456\n789\n 0", '5').unwrap();
+        assert_eq!(door_code(&phone, "DDD\nL\nU").unwrap(), "008");
+    }
+
+    #[test]
+    fn layout_errors() {
+        assert_eq!(Keypad::new("", '5').err(), Some(LayoutError::Empty));
+        assert_eq!(Keypad::new("   \n ", '5').err(), Some(LayoutError::Empty));
+        assert_eq!(Keypad::new(SQUARE, 'A').err(), Some(LayoutError::NoStartKey('A')));
+        assert_eq!(Keypad::new("12\n 51", '5').err(),
+            Some(LayoutError::DuplicateKey { label: '1', first: (1, 1), second: (2, 3) }));
+    }
+
+    #[test]
+    fn reachability() {
+        let diamond = Keypad::new(DIAMOND, '5').unwrap();
+        let d = diamond.find('D').unwrap();
+        assert_eq!(diamond.reachable().len(), 13);
+        assert_eq!(diamond.route(diamond.start(), d), Some("RDRD".to_string()));
+        assert_eq!(diamond.route(d, d), Some(String::new()));
+
+        // 3 is cut off from 5 by the gap
+        let islands = Keypad::new("12 3\n45", '5').unwrap();
+        let labels: String = islands.reachable().into_iter().map(|k| islands.label(k)).collect();
+        assert_eq!(labels, "1245");
+        assert_eq!(islands.route(islands.start(), islands.find('3').unwrap()), None);
+    }
+
+    #[test]
+    fn types_codes() {
+        let square = Keypad::new(SQUARE, '5').unwrap();
+        assert_eq!(square.type_code("1985").unwrap(), vec!["UL", "DDRR", "L", "U"]);
+        assert_eq!(square.type_code("55").unwrap(), vec!["", ""]);
+        assert_eq!(square.type_code("5A").unwrap_err(), CodeError::NoKey('A'));
+
+        let islands = Keypad::new("12 3\n45", '5').unwrap();
+        assert_eq!(islands.type_code("13").unwrap_err(), CodeError::Unreachable('3'));
+    }
+
+    #[test]
+    fn reverse_example() {
+        let diamond = Keypad::new(DIAMOND, '5').unwrap();
+        assert_eq!(instructions_for(&diamond, "5DB3").unwrap(), "\nRDRD\nU\nUU\n");
+    }
+
+    // Every code of two keys, typed and then read back through the forward engine
+    #[test]
+    fn reverse_round_trips() {
+        for &(layout, start) in &[(SQUARE, '5'), (DIAMOND, '5'), ("0123\n4567\n89AB\nCDEF", '0'), ("1 2\n345", '4')] {
+            let keypad = Keypad::new(layout, start).unwrap();
+            let labels: Vec<char> = keypad.keys().into_iter().map(|k| keypad.label(k)).collect();
+            for &a in &labels {
+                for &b in &labels {
+                    let code: String = vec![a, b].into_iter().collect();
+                    let input = instructions_for(&keypad, &code).unwrap();
+                    assert_eq!(door_code(&keypad, &input).unwrap(), code);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn traces_blocked_moves() {
+        let square = Keypad::new(SQUARE, '5').unwrap();
+        let traces = trace(&square, "ULL\nRRDDD").unwrap();
+        let keys = |t: &LineTrace| -> String { t.moves.iter().map(|m| square.label(m.key)).collect() };
+        let blocked = |t: &LineTrace| -> Vec<bool> { t.moves.iter().map(|m| m.blocked).collect() };
+
+        assert_eq!((keys(&traces[0]), square.label(traces[0].end)), ("211".to_string(), '1'));
+        assert_eq!(blocked(&traces[0]), vec![false, false, true]);
+        assert_eq!(keys(&traces[1]), "23699");
+        assert_eq!(blocked(&traces[1]), vec![false, false, false, false, true]);
+    }
+
+    #[test]
+    fn draws_layouts() {
+        let diamond = Keypad::new(DIAMOND, '5').unwrap();
+        assert_eq!(diamond.draw(|k| diamond.label(k).to_string()),
+            vec!["       1", "    2  3  4", " 5  6  7  8  9", "    A  B  C", "       D"]);
+    }
+
+    fn streamed(keypad: &Keypad, input: &str) -> Result<String, StreamError> {
+        let mut code = String::new();
+        stream_code(keypad, input.as_bytes(), |c| {
+            code.push(c);
+            Ok(())
+        })?;
+        Ok(code)
+    }
+
+    #[test]
+    fn streams_codes() {
+        let square = Keypad::new(SQUARE, '5').unwrap();
+        let diamond = Keypad::new(DIAMOND, '5').unwrap();
+        assert_eq!(streamed(&square, EXAMPLE).unwrap(), "1985");
+        assert_eq!(streamed(&diamond, EXAMPLE).unwrap(), "5DB3");
+        assert_eq!(streamed(&square, "ULL\r\nRRDDD\r\nLURDL\r\nUUUUD").unwrap(), "1985");
+        assert_eq!(streamed(&square, "").unwrap(), "");
+
+        match streamed(&square, "ULL\nRRXDD\n") {
+            Err(StreamError::BadDirection { line, column, found }) => assert_eq!((line, column, found), (2, 3, b'X')),
+            other => panic!("expected a bad direction, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn carriage_returns_only_end_lines() {
+        let square = Keypad::new(SQUARE, '5').unwrap();
+        for input in &["U\rD\n", "UD\r"] {
+            let e = door_code(&square, input).unwrap_err();
+            assert_eq!((e.line, e.column, e.token.as_str()), (1, input.find('\r').unwrap() + 1, "\r"));
+            match streamed(&square, input) {
+                Err(StreamError::BadDirection { line, column, found }) =>
+                    assert_eq!((line, column, found), (e.line, e.column, b'\r')),
+                other => panic!("expected a bad direction, got {:?}", other),
+            }
+        }
+    }
+
+    #[test]
+    fn bad_direction() {
+        let e = Day2.part1("ULL\nRRXDD\n").unwrap_err();
+        assert_eq!((e.line, e.column, e.token.as_str()), (2, 3, "X"));
     }
-    println!("");
 }
@@ -1,61 +1,296 @@
-use get_input::get_input;
-
-const IS_PART1: bool = false;
-
-pub fn solve() {
-    let input = get_input("assets/day/3/input");
-
-    if IS_PART1 {
-        let solution: u32 = input.lines().filter_map(|line| {
-            let mut words = line.split_whitespace();
-            let a = words.next().unwrap().parse::<i32>().unwrap();
-            let b = words.next().unwrap().parse::<i32>().unwrap();
-            let c = words.next().unwrap().parse::<i32>().unwrap();
-            if words.count() != 0 {
-                panic!("Too many inputs on one line");
-            }
-
-            if a + b > c && b + c > a && a + c > b {
-                Some(1u32)
-            } else {
-                None
-            }
-        }).sum();
-
-        println!("Part 1: {} possible triangles", solution);
-    } else {
-        let mut columns = Vec::new();
-        let mut triangles = Vec::new();
-        for line in input.lines() {
-            let mut words = line.split_whitespace();
-            columns.push(
-                (words.next().unwrap().parse::<i32>().unwrap(),
-                words.next().unwrap().parse::<i32>().unwrap(),
-                words.next().unwrap().parse::<i32>().unwrap())
-            );
-            if words.count() != 0 {
-                panic!("Too many inputs on one line");
-            }
-
-            if columns.len() == 3 {
-                let (c1, c2, c3) = columns.pop().unwrap();
-                let (b1, b2, b3) = columns.pop().unwrap();
-                let (a1, a2, a3) = columns.pop().unwrap();
-
-                triangles.push((a1, b1, c1));
-                triangles.push((a2, b2, c2));
-                triangles.push((a3, b3, c3));
-            }
+use std::cmp::Ordering;
+use std::fmt;
+
+use columns::{Grouping, GroupedExt, Shape};
+use parse_error::ParseError;
+use solver::{Answer, Solver};
+
+// Sides are whole and positive, and anything up to u64::MAX is fine: every
+// sum and square below is done in u128, where it can't overflow
+fn parse_side<'a, I: Iterator<Item = &'a str>>(input: &str, line: &'a str, words: &mut I)
+    -> Result<u64, ParseError> {
+    let word = match words.next() {
+        Some(word) => word,
+        None => return Err(ParseError::at(input, &line[line.len()..], "three side lengths")),
+    };
+    let digits = word.strip_prefix('-').unwrap_or(word);
+    let numeric = !digits.is_empty() && digits.bytes().all(|b| b.is_ascii_digit());
+    match word.parse::<u64>() {
+        Ok(0) => Err(ParseError::at(input, word, "a positive side length")),
+        Ok(side) => Ok(side),
+        Err(_) if numeric && word.starts_with('-') => Err(ParseError::at(input, word, "a positive side length")),
+        Err(_) if numeric => Err(ParseError::at(input, word, "a side length of at most 18446744073709551615")),
+        Err(_) => Err(ParseError::at(input, word, "a side length")),
+    }
+}
+
+pub fn parse_line(input: &str, line: &str) -> Result<Vec<u64>, ParseError> {
+    let mut words = line.split_whitespace();
+    let a = parse_side(input, line, &mut words)?;
+    let b = parse_side(input, line, &mut words)?;
+    let c = parse_side(input, line, &mut words)?;
+    if let Some(extra) = words.next() {
+        return Err(ParseError::at(input, extra, "only three side lengths"));
+    }
+    Ok(vec![a, b, c])
+}
+
+// The rows of the input, parsed as they're read
+pub fn rows<'a>(input: &'a str) -> impl Iterator<Item = Result<Vec<u64>, ParseError>> + 'a {
+    input.lines().map(move |line| parse_line(input, line))
+}
+
+// Points at the row, counted from 0, that couldn't be regrouped
+pub fn shape_error(input: &str, row: usize, shape: Shape) -> ParseError {
+    let line = input.lines().nth(row).unwrap_or(&input[input.len()..]);
+    match shape {
+        Shape::Width(_) => ParseError::at(input, line, "three side lengths"),
+        Shape::Leftover => ParseError::at(input, line, "enough rows to fill the last block"),
+    }
+}
+
+// The candidates as read with any grouping of the rows
+pub fn candidates<'a>(input: &'a str, grouping: Grouping)
+    -> impl Iterator<Item = Result<Vec<u64>, ParseError>> + 'a {
+    rows(input).grouped(3, grouping, move |row, shape| shape_error(input, row, shape))
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Sides {
+    Equilateral,
+    Isosceles,
+    Scalene,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Angles {
+    Acute,
+    Right,
+    Obtuse,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Kind {
+    Invalid,
+    // the two shorter sides exactly span the longest, so it's flat
+    Degenerate,
+    Valid(Sides, Angles),
+}
+
+impl fmt::Display for Kind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match *self {
+            Kind::Invalid => "invalid".to_string(),
+            Kind::Degenerate => "degenerate".to_string(),
+            Kind::Valid(sides, angles) => format!("{} {}",
+                match sides {
+                    Sides::Equilateral => "equilateral",
+                    Sides::Isosceles => "isosceles",
+                    Sides::Scalene => "scalene",
+                },
+                match angles {
+                    Angles::Acute => "acute",
+                    Angles::Right => "right",
+                    Angles::Obtuse => "obtuse",
+                }),
+        };
+        f.pad(&name)
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Triangle {
+    pub a: u64,
+    pub b: u64,
+    pub c: u64,
+}
+
+impl Triangle {
+    pub fn from_sides(sides: &[u64]) -> Triangle {
+        Triangle { a: sides[0], b: sides[1], c: sides[2] }
+    }
+
+    // Each side shorter than the other two together
+    pub fn is_valid(&self) -> bool {
+        let (x, y, z) = self.sorted();
+        x > 0 && x + y > z
+    }
+
+    fn sorted(&self) -> (u128, u128, u128) {
+        let mut sides = [self.a as u128, self.b as u128, self.c as u128];
+        sides.sort();
+        (sides[0], sides[1], sides[2])
+    }
+
+    pub fn kind(&self) -> Kind {
+        let (x, y, z) = self.sorted();
+        if x == 0 || x + y < z {
+            return Kind::Invalid;
+        }
+        if x + y == z {
+            return Kind::Degenerate;
         }
 
-        let solution: u32 = triangles.iter().filter_map(|&(a, b, c)| {
-            if a + b > c && b + c > a && a + c > b {
-                Some(1u32)
-            } else {
-                None
-            }
-        }).sum();
+        let sides = if x == z {
+            Sides::Equilateral
+        } else if x == y || y == z {
+            Sides::Isosceles
+        } else {
+            Sides::Scalene
+        };
+        // the largest angle is opposite the longest side. x² + y² against z²
+        // could pass u128::MAX, x² against z² - y² can't
+        let angles = match (x * x).cmp(&((z - y) * (z + y))) {
+            Ordering::Greater => Angles::Acute,
+            Ordering::Equal => Angles::Right,
+            Ordering::Less => Angles::Obtuse,
+        };
+        Kind::Valid(sides, angles)
+    }
+
+    pub fn perimeter(&self) -> u128 {
+        self.a as u128 + self.b as u128 + self.c as u128
+    }
+
+    // Heron's formula, rearranged to keep precision on thin triangles
+    pub fn area(&self) -> f64 {
+        let (x, y, z) = self.sorted();
+        let (x, y, z) = (x as f64, y as f64, z as f64);
+        0.25 * ((z + (y + x)) * (x - (z - y)) * (x + (z - y)) * (z + (y - x))).sqrt()
+    }
+}
+
+pub fn count_valid<I>(candidates: I) -> Result<usize, ParseError>
+    where I: Iterator<Item = Result<Vec<u64>, ParseError>> {
+    let mut solution = 0;
+    for sides in candidates {
+        if Triangle::from_sides(&sides?).is_valid() {
+            solution += 1;
+        }
+    }
+    Ok(solution)
+}
+
+pub struct Day3;
+
+impl Solver for Day3 {
+    fn part1(&self, input: &str) -> Result<Answer, ParseError> {
+        Ok(count_valid(candidates(input, Grouping::Rows))?.into())
+    }
+
+    // Triangles run down the columns, three rows at a time
+    fn part2(&self, input: &str) -> Result<Answer, ParseError> {
+        Ok(count_valid(candidates(input, Grouping::Columns(3)))?.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+  101 301 501
+  102 302 502
+  103 303 503
+  201 401 601
+  202 402 602
+  203 403 603
+";
+
+    #[test]
+    fn part1_examples() {
+        assert_eq!(Day3.part1("5 10 25").unwrap(), Answer::Integer(0));
+        assert_eq!(Day3.part1(EXAMPLE).unwrap(), Answer::Integer(3));
+    }
+
+    #[test]
+    fn part2_example() {
+        assert_eq!(Day3.part2(EXAMPLE).unwrap(), Answer::Integer(6));
+    }
+
+    #[test]
+    fn groupings() {
+        let count = |grouping| count_valid(candidates(EXAMPLE, grouping)).unwrap();
+        assert_eq!(count(Grouping::Columns(6)), 6);
+        // 101 102 103, 201 202 203, 301 302 303, ... then 601 602 603
+        assert_eq!(count(Grouping::Transposed), 6);
+        assert_eq!(count(Grouping::Rows), 3);
+        assert_eq!(count_valid(candidates("3 4\n", Grouping::Rows)).unwrap_err().line, 1);
+    }
+
+    #[test]
+    fn leftover_rows() {
+        let e = Day3.part2("3 4 5\n3 4 5\n3 4 5\n1 1 1\n 2 2 2\n").unwrap_err();
+        assert_eq!((e.line, e.column, e.token.as_str()), (4, 1, "1 1 1"));
+        assert_eq!(e.expected, "enough rows to fill the last block");
+    }
+
+    #[test]
+    fn validity() {
+        assert!(Triangle { a: 3, b: 4, c: 5 }.is_valid());
+        assert!(!Triangle { a: 5, b: 10, c: 25 }.is_valid());
+        assert!(!Triangle { a: 1, b: 2, c: 3 }.is_valid());
+    }
+
+    #[test]
+    fn classifies() {
+        let kind = |a, b, c| Triangle { a, b, c }.kind();
+        assert_eq!(kind(5, 10, 25), Kind::Invalid);
+        assert_eq!(kind(0, 4, 4), Kind::Invalid);
+        assert_eq!(kind(1, 2, 3), Kind::Degenerate);
+        assert_eq!(kind(3, 4, 5), Kind::Valid(Sides::Scalene, Angles::Right));
+        assert_eq!(kind(7, 7, 7), Kind::Valid(Sides::Equilateral, Angles::Acute));
+        assert_eq!(kind(5, 5, 8), Kind::Valid(Sides::Isosceles, Angles::Obtuse));
+        assert_eq!(kind(4, 5, 6).to_string(), "scalene acute");
+    }
+
+    #[test]
+    fn geometry() {
+        let t = Triangle { a: 3, b: 4, c: 5 };
+        assert_eq!((t.perimeter(), t.area()), (12, 6.0));
+        assert_eq!(Triangle { a: 1, b: 2, c: 3 }.area(), 0.0);
+        assert!((Triangle { a: 2, b: 2, c: 2 }.area() - 3f64.sqrt()).abs() < 1e-12);
+    }
+
+    #[test]
+    fn huge_sides() {
+        let max = u64::MAX;
+        assert!(Triangle { a: max, b: max, c: max }.is_valid());
+        assert!(Triangle { a: 1, b: max, c: max }.is_valid());
+        assert!(!Triangle { a: 1, b: max - 1, c: max }.is_valid());
+        assert_eq!(Triangle { a: max, b: max, c: max }.kind(), Kind::Valid(Sides::Equilateral, Angles::Acute));
+        assert_eq!(Triangle { a: 1, b: max - 1, c: max }.kind(), Kind::Degenerate);
+        assert_eq!(Triangle { a: 2, b: max, c: max }.kind(), Kind::Valid(Sides::Isosceles, Angles::Acute));
+        assert_eq!(Triangle { a: max, b: max, c: max }.perimeter(), 3 * max as u128);
+
+        // 3 * 2^61, 4 * 2^61, 5 * 2^61 still right-angled, exactly
+        let k = 1 << 61;
+        assert_eq!(Triangle { a: 3 * k, b: 4 * k, c: 5 * k }.kind(), Kind::Valid(Sides::Scalene, Angles::Right));
+        assert_eq!(Triangle { a: 3 * k, b: 4 * k, c: 5 * k - 1 }.kind(), Kind::Valid(Sides::Scalene, Angles::Acute));
+        assert_eq!(Day3.part1("18446744073709551615 18446744073709551615 1").unwrap(), Answer::Integer(1));
+    }
+
+    #[test]
+    fn rejects_bad_sides() {
+        let e = Day3.part1("5 10 25\n5 -10 25\n").unwrap_err();
+        assert_eq!((e.line, e.column, e.token.as_str()), (2, 3, "-10"));
+        assert_eq!(e.expected, "a positive side length");
+
+        let e = Day3.part1("0 10 25").unwrap_err();
+        assert_eq!((e.line, e.column, e.token.as_str()), (1, 1, "0"));
+        assert_eq!(e.expected, "a positive side length");
+
+        let e = Day3.part1("5 18446744073709551616 25").unwrap_err();
+        assert_eq!((e.line, e.column, e.token.as_str()), (1, 3, "18446744073709551616"));
+        assert_eq!(e.expected, "a side length of at most 18446744073709551615");
+
+        let e = Day3.part1("5 ten 25").unwrap_err();
+        assert_eq!(e.expected, "a side length");
+    }
 
-        println!("Part 2: {} possible triangles", solution);
+    #[test]
+    fn missing_side() {
+        let e = Day3.part1("5 10 25\n5 10\n").unwrap_err();
+        assert_eq!((e.line, e.column, e.token.as_str()), (2, 5, ""));
     }
 }
@@ -1,22 +1,44 @@
 use std::collections::HashMap;
 
-use get_input::get_input;
+use parse_error::ParseError;
+use solver::{Answer, Solver};
 
-const IS_PART1: bool = false;
+// Splits "aaaaa-bbb-z-y-x-123[abxyz]" into its encrypted name, sector ID and checksum
+fn parse_room<'a>(input: &str, room: &'a str) -> Result<(&'a str, u32, &'a str), ParseError> {
+    let end = &room[room.len()..];
+    let open = room.find('[')
+        .ok_or_else(|| ParseError::at(input, end, "'[' followed by a checksum"))?;
+    if !room.ends_with(']') {
+        return Err(ParseError::at(input, end, "']' closing the checksum"));
+    }
+    if open + 1 == room.len() - 1 {
+        return Err(ParseError::at(input, &room[open + 1..open + 1], "a checksum"));
+    }
+    let checksum = &room[open + 1..room.len() - 1];
 
-fn sector_id(room_name: &str) -> Option<(&str, u32)> {
-    let mut name_parts = room_name.split(|c| { c == '-' || c == '[' || c == ']' });
-    let _ = name_parts.next_back(); // skip empty string due to delimiter ']' at end of input
-    let checksum = name_parts.next_back().unwrap();
-    let sector_id = name_parts.next_back().unwrap().parse::<u32>().unwrap();
+    let head = &room[..open];
+    let dash = head.rfind('-')
+        .ok_or_else(|| ParseError::at(input, head, "an encrypted name followed by '-'"))?;
+    let id = &head[dash + 1..];
+    let id = id.parse::<u32>()
+        .map_err(|_| ParseError::at(input, id, "a sector ID"))?;
+
+    let name = &head[..dash];
+    if let Some((i, c)) = name.char_indices().find(|&(_, c)| c != '-' && !c.is_ascii_lowercase()) {
+        return Err(ParseError::at(input, &name[i..i + c.len_utf8()], "a lowercase letter or '-'"));
+    }
+
+    Ok((name, id, checksum))
+}
+
+fn sector_id<'a>(input: &str, room_name: &'a str) -> Result<Option<(&'a str, u32)>, ParseError> {
+    let (name, sector_id, checksum) = parse_room(input, room_name)?;
 
     let mut histogram = HashMap::new();
 
-    for part in name_parts {
-        for c in part.chars() {
-            let entry = histogram.entry(c).or_insert(0u32);
-            *entry += 1u32;
-        }
+    for c in name.chars().filter(|&c| c != '-') {
+        let entry = histogram.entry(c).or_insert(0u32);
+        *entry += 1u32;
     }
 
     let mut v: Vec<(char, u32)> = histogram.iter().map(|(k,v)| (*k, *v)).collect();
@@ -31,19 +53,29 @@ fn sector_id(room_name: &str) -> Option<(&str, u32)> {
     let actual_checksum: String = v.iter().take(checksum.len()).map(|&(c,_)| c).collect();
 
     if actual_checksum == checksum {
-        Some((&room_name[..room_name.rfind('-').unwrap()], sector_id))
+        Ok(Some((name, sector_id)))
     } else {
-        None
+        Ok(None)
     }
 }
 
+fn real_rooms(input: &str) -> Result<Vec<(&str, u32)>, ParseError> {
+    let mut rooms = Vec::new();
+    for line in input.lines() {
+        if let Some(room) = sector_id(input, line)? {
+            rooms.push(room);
+        }
+    }
+    Ok(rooms)
+}
+
 fn decode(s: &str, id: u32) -> String {
-    let a = 'a' as u8;
+    let a = b'a';
     let rot = (id % 26) as u8; // get rid of full rotations
     let mut decoded = String::with_capacity(s.len());
 
     for c in s.bytes() {
-        if c == '-' as u8 {
+        if c == b'-' {
             decoded.push(' ');
         } else {
             decoded.push(((c - a + rot) % 26 + a) as char);
@@ -53,17 +85,78 @@ fn decode(s: &str, id: u32) -> String {
     decoded
 }
 
-pub fn solve() {
-    let input = get_input("assets/day/4/input");
-    let real_rooms = input.lines().filter_map(sector_id);
+// Every real room's sector ID and decrypted name, in input order
+pub fn rooms(input: &str) -> Result<Vec<(u32, String)>, ParseError> {
+    Ok(real_rooms(input)?.iter().map(|&(name, id)| (id, decode(name, id))).collect())
+}
+
+pub struct Day4;
 
-    if IS_PART1 {
-        let solution: u32 = real_rooms.map(|(_,c)| c).sum();
-        println!("Sector IDs sum is {}", solution);
-    } else {
-        for (encoded_name, id) in real_rooms {
-            let decoded_name = decode(encoded_name, id);
-            println!("{}: {}", id, decoded_name);
-        }
+impl Solver for Day4 {
+    fn part1(&self, input: &str) -> Result<Answer, ParseError> {
+        let solution: u32 = real_rooms(input)?.iter().map(|&(_,c)| c).sum();
+        Ok(solution.into())
+    }
+
+    // The room the North Pole objects are stored in
+    fn part2(&self, input: &str) -> Result<Answer, ParseError> {
+        rooms(input)?.into_iter()
+            .find(|(_, name)| name.contains("northpole object"))
+            .map(|(id, _)| id.into())
+            .ok_or_else(|| ParseError::at(input, &input[input.len()..], "a room storing northpole objects"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+aaaaa-bbb-z-y-x-123[abxyz]
+a-b-c-d-e-f-g-h-987[abcde]
+not-a-real-room-404[oarel]
+totally-real-room-200[decoy]
+";
+
+    #[test]
+    fn real_room_examples() {
+        let rooms = real_rooms(EXAMPLE).unwrap();
+        assert_eq!(rooms, vec![("aaaaa-bbb-z-y-x", 123),
+                               ("a-b-c-d-e-f-g-h", 987),
+                               ("not-a-real-room", 404)]);
+    }
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(Day4.part1(EXAMPLE).unwrap(), Answer::Integer(1514));
+    }
+
+    #[test]
+    fn decode_example() {
+        assert_eq!(decode("qzmt-zixmtkozy-ivhz", 343), "very encrypted name");
+    }
+
+    #[test]
+    fn part2_finds_storage() {
+        let input = "qzmt-zixmtkozy-ivhz-343[zimth]\nnorthpole-object-storage-260[oetra]\n";
+        assert_eq!(rooms(input).unwrap(), vec![(343, "very encrypted name".to_string()),
+                                               (260, "northpole object storage".to_string())]);
+        assert_eq!(Day4.part2(input).unwrap(), Answer::Integer(260));
+
+        let e = Day4.part2(EXAMPLE).unwrap_err();
+        assert_eq!((e.line, e.expected.as_str()), (5, "a room storing northpole objects"));
+    }
+
+    #[test]
+    fn bad_sector_id() {
+        let e = Day4.part1("a-b-c-x9[abc]").unwrap_err();
+        assert_eq!((e.line, e.column, e.token.as_str()), (1, 7, "x9"));
+    }
+
+    #[test]
+    fn empty_checksum() {
+        let e = Day4.part1("a-1[]").unwrap_err();
+        assert_eq!((e.line, e.column, e.token.as_str()), (1, 5, ""));
+        assert_eq!(e.expected, "a checksum");
     }
 }
@@ -4,25 +4,46 @@ use std::collections::{BinaryHeap, HashSet};
 use std::sync::{Arc, Mutex};
 use std::thread;
 
-// Quick timing results on my laptop
-//
-// Elapsed |        |        |
-// Time    | Single | Multi  |
-// --------|--------|--------|
-// Part 1  |  3.5 s |  2.5 s |
-// Part 2  | 11.9 s |  7.7 s |
-// --------|--------|--------|
-
-const IS_PART1: bool = false;
+use parse_error::ParseError;
+use solver::{Answer, Part, Solver};
+
+// For timings run `advent2016 bench --day 5 --runs 3` in a release build,
+// flipping IS_CONCURRENT to compare against the single threaded version.
+
 const IS_CONCURRENT: bool = true;
 const NUM_CPUS: usize = 4; // assuming my machine
 
-pub fn solve() {
-    if !IS_CONCURRENT {
-        return solve_no_concurrency();
+pub struct Day5;
+
+// A blank input has no door ID to hash
+fn door_id(input: &str) -> ::std::result::Result<&str, ParseError> {
+    let id = input.trim();
+    if id.is_empty() {
+        return Err(ParseError::at(input, &input[input.len()..], "a door ID"));
+    }
+    Ok(id)
+}
+
+impl Solver for Day5 {
+    fn part1(&self, input: &str) -> ::std::result::Result<Answer, ParseError> {
+        Ok(crack(Part::One, door_id(input)?).into())
+    }
+
+    fn part2(&self, input: &str) -> ::std::result::Result<Answer, ParseError> {
+        Ok(crack(Part::Two, door_id(input)?).into())
     }
+}
+
+// `sh` has already been fed the door ID; it is copied, not consumed
+fn hash_nonce(mut sh: Md5, nonce: i32) -> String {
+    sh.input_str(&nonce.to_string());
+    sh.result_str()
+}
 
-    let input = "cxdnnyjw";
+fn crack(part: Part, input: &str) -> String {
+    if !IS_CONCURRENT {
+        return crack_no_concurrency(part, input);
+    }
 
     let nonces = Arc::new(Mutex::new(0..));
     let results = Arc::new(Mutex::new(BinaryHeap::new()));
@@ -34,39 +55,33 @@ pub fn solve() {
         let nonces = nonces.clone();
         let results = results.clone();
         let index_set = index_set.clone();
+        let input = input.to_string();
 
         threads.push(thread::spawn(move || {
             let mut sh = Md5::new();
-            sh.input_str(input);
+            sh.input_str(&input);
 
             while results.lock().unwrap().len() < 8 {
                 let nonce = nonces.lock().unwrap().next().unwrap();
-                let mut sh = sh.clone();
-
-                sh.input_str(&nonce.to_string());
-                let hash = sh.result_str();
+                let hash = hash_nonce(sh, nonce);
 
                 if hash.starts_with("00000") {
                     let ch = hash.chars().nth(5).unwrap();
-                    if IS_PART1 {
-                        println!("{}: {}", nonce, ch);
+                    if part == Part::One {
                         results.lock().unwrap().push(Result {
                             priority: nonce,
-                            ch: ch,
+                            ch,
                         });
-                    } else if ch >= '0' && ch <= '7' {
+                    } else if ('0'..='7').contains(&ch) {
                         let i = (ch as u8 - b'0') as i32;
                         let mut index_set = index_set.lock().unwrap();
                         if !index_set.contains(&i) {
                             let ch = hash.chars().nth(6).unwrap();
-                            println!("{}: {} {}", nonce, i, ch);
                             index_set.insert(i);
                             results.lock().unwrap().push(Result {
                                 priority: i,
-                                ch: ch,
+                                ch,
                             });
-                        } else {
-                            println!("{}: {} SKIPPED", nonce, i);
                         }
                     }
                 }
@@ -84,7 +99,7 @@ pub fn solve() {
     let password = results.lock().unwrap().clone()
         .into_sorted_vec().iter()
         .take(8).map(|r| r.ch).collect::<String>();
-    println!("Password cracked!\npassword: {}", password);
+    password
 }
 
 #[derive(Copy, Clone, Eq, PartialEq)]
@@ -108,45 +123,32 @@ impl PartialOrd for Result {
 
 // My original solution before diving into std::sync
 // for some concurrency fun
-pub fn solve_no_concurrency() {
-    let input = "cxdnnyjw";
-
+fn crack_no_concurrency(part: Part, input: &str) -> String {
     let mut sh = Md5::new();
     sh.input_str(input);
 
-    if IS_PART1 {
-        let password = (0..).filter_map(|nonce| {
-            let mut sh = sh.clone();
-            sh.input_str(nonce.to_string().as_str());
-            let hash = sh.result_str();
+    let password = if part == Part::One {
+        (0..).filter_map(|nonce| {
+            let hash = hash_nonce(sh, nonce);
             if hash.starts_with("00000") {
-                let ch = hash.chars().nth(5).unwrap();
-                println!("{}: {}", nonce, ch);
-                Some(ch)
+                hash.chars().nth(5)
             } else {
                 None
             }
-        }).take(8).collect::<String>();
-
-        println!("{}", password);
+        }).take(8).collect::<String>()
     } else {
         let mut password: Vec<Option<char>> = vec![None; 8];
 
         for nonce in 0.. {
-            let mut sh = sh.clone();
-            sh.input_str(nonce.to_string().as_str());
-            let hash = sh.result_str();
+            let hash = hash_nonce(sh, nonce);
             if hash.starts_with("00000") {
                 let i = hash.chars().nth(5).unwrap();
-                match i {
-                    '0'...'7' => {
-                        let i = (i as u8 - b'0') as usize;
-                        if password[i].is_none() {
-                            password[i] = Some(
-                                hash.chars().nth(6).unwrap());
-                        }
-                    },
-                    _ => ()
+                if let '0'..='7' = i {
+                    let i = (i as u8 - b'0') as usize;
+                    if password[i].is_none() {
+                        password[i] = Some(
+                            hash.chars().nth(6).unwrap());
+                    }
                 }
                 if !password.contains(&None) {
                     break;
@@ -154,9 +156,8 @@ pub fn solve_no_concurrency() {
             }
         }
 
-        let password = password.iter().map(|c| c.unwrap()).collect::<String>();
-        println!("{}", password);
-    }
+        password.iter().map(|c| c.unwrap()).collect::<String>()
+    };
 
     // iterator to produce key+nonce enumerated values where nonce starts at 0
     // and is incremented forever
@@ -175,4 +176,50 @@ pub fn solve_no_concurrency() {
     // Or...
     // can we do a take(8).collect().to_string() on a parallel iterator?
     // No, I cannot figure out how to do this quickly enough.
+
+    password
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn door(id: &str) -> Md5 {
+        let mut sh = Md5::new();
+        sh.input_str(id);
+        sh
+    }
+
+    #[test]
+    fn part1_hashes() {
+        assert!(hash_nonce(door("abc"), 3231929).starts_with("000001"));
+        assert!(hash_nonce(door("abc"), 5017308).starts_with("000008f82"));
+        assert!(hash_nonce(door("abc"), 5278568).starts_with("00000f"));
+        assert!(!hash_nonce(door("abc"), 0).starts_with("00000"));
+    }
+
+    #[test]
+    fn part2_hashes() {
+        assert!(hash_nonce(door("abc"), 3231929).starts_with("0000015"));
+        assert!(hash_nonce(door("abc"), 5017308).starts_with("000008f"));
+        assert!(hash_nonce(door("abc"), 5357525).starts_with("000004e"));
+    }
+
+    #[test]
+    fn blank_door_id() {
+        assert_eq!(Day5.part1(" \n").unwrap_err().expected, "a door ID");
+    }
+
+    // Slow, hashes millions of nonces: cargo test --release -- --ignored
+    #[test]
+    #[ignore]
+    fn part1_example() {
+        assert_eq!(Day5.part1("abc").unwrap(), Answer::Text("18f47a30".to_string()));
+    }
+
+    #[test]
+    #[ignore]
+    fn part2_example() {
+        assert_eq!(Day5.part2("abc").unwrap(), Answer::Text("05ace8e3".to_string()));
+    }
 }
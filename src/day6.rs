@@ -1,20 +1,19 @@
 use std::collections::HashMap;
 
-use get_input::get_input;
+use parse_error::ParseError;
+use solver::{Answer, Part, Solver};
 
-const IS_PART1: bool = false;
-
-pub fn solve() {
-    let input = get_input("assets/day/6/input");
-    let msg_len = input.lines().next().unwrap().chars().count();
-
-    println!("{}", msg_len);
+fn error_correct(part: Part, input: &str) -> Result<String, ParseError> {
+    let first = input.lines().next().unwrap_or("");
+    let msg_len = first.chars().count();
+    if msg_len == 0 {
+        return Err(ParseError::at(input, first, "a message"));
+    }
 
     let mut correct_msg = Vec::with_capacity(msg_len);
     correct_msg.resize(msg_len, ' ');
 
-    let mut max = Vec::with_capacity(msg_len);
-    max.resize(msg_len, 0);
+    let mut max = vec![0; msg_len];
 
     let mut histos = Vec::with_capacity(msg_len);
     for _ in 0..msg_len {
@@ -22,25 +21,84 @@ pub fn solve() {
     }
 
     for msg in input.lines() {
-        for (i, c) in msg.chars().enumerate() {
+        if msg.chars().count() < msg_len {
+            let expected = format!("{} characters like the first line", msg_len);
+            return Err(ParseError::at(input, &msg[msg.len()..], &expected));
+        }
+
+        for (i, (offset, c)) in msg.char_indices().enumerate() {
+            if i == msg_len {
+                let expected = format!("only {} characters like the first line", msg_len);
+                return Err(ParseError::at(input, &msg[offset..], &expected));
+            }
+
             let entry = histos[i].entry(c).or_insert(0);
             *entry += 1;
 
-            if IS_PART1 && *entry > max[i] {
+            if part == Part::One && *entry > max[i] {
                 max[i] = *entry;
                 correct_msg[i] = c;
             }
         }
     };
 
-    if IS_PART1 {
-        let message: String = correct_msg.into_iter().collect();
-        println!("{}", message);
+    if part == Part::One {
+        Ok(correct_msg.into_iter().collect())
     } else {
-        let message: String =
-            histos.into_iter()
-                  .map(|h| h.iter().min_by(|a,b| a.1.cmp(b.1)).unwrap().0.clone())
-                  .collect();
-        println!("{}", message);
+        Ok(histos.into_iter()
+                 .map(|h| *h.iter().min_by(|a,b| a.1.cmp(b.1)).unwrap().0)
+                 .collect())
+    }
+}
+
+pub struct Day6;
+
+impl Solver for Day6 {
+    fn part1(&self, input: &str) -> Result<Answer, ParseError> {
+        error_correct(Part::One, input).map(Answer::from)
+    }
+
+    fn part2(&self, input: &str) -> Result<Answer, ParseError> {
+        error_correct(Part::Two, input).map(Answer::from)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+eedadn
+drvtee
+eandsr
+raavrd
+atevrs
+tsrnev
+sdttsa
+rasrtv
+nssdts
+ntnada
+svetve
+tesnvt
+vntsnd
+vrdear
+dvrsen
+enarar
+";
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(Day6.part1(EXAMPLE).unwrap(), Answer::Text("easter".to_string()));
+    }
+
+    #[test]
+    fn part2_example() {
+        assert_eq!(Day6.part2(EXAMPLE).unwrap(), Answer::Text("advent".to_string()));
+    }
+
+    #[test]
+    fn blank_message() {
+        let e = Day6.part1("\nabc\n").unwrap_err();
+        assert_eq!((e.line, e.column, e.expected.as_str()), (1, 1, "a message"));
     }
 }
@@ -1,10 +1,152 @@
+use std::error;
+use std::fmt;
 use std::fs::File;
-use std::io::Read;
+use std::io::{self, Read};
+use std::path::PathBuf;
 
-pub fn get_input(file_path: &'static str) -> String {
-    let mut file = File::open(file_path).expect("Failed to open input file");
-    let mut input = String::new();
-    file.read_to_string(&mut input).expect("Failed to read input file into String");
+use provider::FetchError;
 
-    input
+// Where to read a puzzle input from
+pub enum Source {
+    Path(PathBuf),
+    Stdin,
+    Inline(String),
+}
+
+impl Source {
+    // Command line convention: "-" means stdin, anything else is a path
+    pub fn from_arg(arg: &str) -> Source {
+        if arg == "-" {
+            Source::Stdin
+        } else {
+            Source::Path(PathBuf::from(arg))
+        }
+    }
+}
+
+impl fmt::Display for Source {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Source::Path(ref path) => write!(f, "{}", path.display()),
+            Source::Stdin => write!(f, "<stdin>"),
+            Source::Inline(_) => write!(f, "<inline>"),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum InputError {
+    NotFound { day: u32, source: String },
+    Unreadable { day: u32, source: String, error: io::Error },
+    NotUtf8 { day: u32, source: String },
+    Empty { day: u32, source: String },
+    Fetch { day: u32, url: String, error: FetchError },
+}
+
+impl fmt::Display for InputError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            InputError::NotFound { day, ref source } =>
+                write!(f, "day {}: input {} not found", day, source),
+            InputError::Unreadable { day, ref source, ref error } =>
+                write!(f, "day {}: could not read input {}: {}", day, source, error),
+            InputError::NotUtf8 { day, ref source } =>
+                write!(f, "day {}: input {} is not valid UTF-8", day, source),
+            InputError::Empty { day, ref source } =>
+                write!(f, "day {}: input {} is empty", day, source),
+            InputError::Fetch { day, ref url, ref error } =>
+                write!(f, "day {}: input is not cached and fetching {} failed: {}", day, url, error),
+        }
+    }
+}
+
+impl error::Error for InputError {}
+
+fn read_bytes(day: u32, source: &Source) -> Result<Vec<u8>, InputError> {
+    let unreadable = |error| InputError::Unreadable { day, source: source.to_string(), error };
+    let mut bytes = Vec::new();
+
+    match *source {
+        Source::Path(ref path) => {
+            let mut file = File::open(path).map_err(|e| {
+                if e.kind() == io::ErrorKind::NotFound {
+                    InputError::NotFound { day, source: source.to_string() }
+                } else {
+                    unreadable(e)
+                }
+            })?;
+            file.read_to_end(&mut bytes).map_err(unreadable)?;
+        },
+        Source::Stdin => {
+            io::stdin().read_to_end(&mut bytes).map_err(unreadable)?;
+        },
+        Source::Inline(ref s) => bytes.extend_from_slice(s.as_bytes()),
+    }
+
+    Ok(bytes)
+}
+
+pub fn get_input(day: u32, source: &Source) -> Result<String, InputError> {
+    let bytes = read_bytes(day, source)?;
+    let input = String::from_utf8(bytes)
+        .map_err(|_| InputError::NotUtf8 { day, source: source.to_string() })?;
+
+    // only a file with nothing in it at all; blank lines are left to the
+    // solver, as a blank day 2 line still types a key
+    if input.is_empty() {
+        return Err(InputError::Empty { day, source: source.to_string() });
+    }
+
+    Ok(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+    use std::fs;
+    use std::process;
+
+    fn input(day: u32, text: &str) -> Result<String, InputError> {
+        get_input(day, &Source::Inline(text.to_string()))
+    }
+
+    #[test]
+    fn blank_inputs() {
+        assert!(matches!(input(1, ""), Err(InputError::Empty { day: 1, .. })));
+        assert_eq!(input(2, "\n\n").unwrap(), "\n\n");
+        assert_eq!(input(1, " \n").unwrap(), " \n");
+    }
+
+    #[test]
+    fn sources() {
+        assert!(matches!(Source::from_arg("-"), Source::Stdin));
+        assert!(matches!(Source::from_arg("in.txt"), Source::Path(ref p) if p == &PathBuf::from("in.txt")));
+        assert_eq!(Source::from_arg("-").to_string(), "<stdin>");
+        assert_eq!(Source::Inline("R2".to_string()).to_string(), "<inline>");
+    }
+
+    #[test]
+    fn unreadable_files() {
+        let dir = env::temp_dir().join(format!("advent2016-get-input-{}", process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+
+        let missing = Source::Path(dir.join("missing"));
+        let e = get_input(3, &missing).unwrap_err();
+        assert!(matches!(e, InputError::NotFound { day: 3, .. }));
+        assert_eq!(e.to_string(), format!("day 3: input {} not found", dir.join("missing").display()));
+
+        let latin1 = dir.join("latin1");
+        fs::write(&latin1, b"caf\xe9\n").unwrap();
+        let e = get_input(4, &Source::Path(latin1.clone())).unwrap_err();
+        assert!(matches!(e, InputError::NotUtf8 { day: 4, .. }));
+        assert_eq!(e.to_string(), format!("day 4: input {} is not valid UTF-8", latin1.display()));
+
+        // a directory opens but can't be read
+        let e = get_input(5, &Source::Path(dir.clone())).unwrap_err();
+        assert!(matches!(e, InputError::Unreadable { day: 5, .. }));
+
+        let _ = fs::remove_dir_all(&dir);
+    }
 }
@@ -0,0 +1,111 @@
+use std::collections::HashMap;
+use std::io::{self, Write};
+use std::thread;
+use std::time::Duration;
+
+use day2::{Key, Keypad, LineTrace};
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Style {
+    Animate { delay_ms: u64 },
+    Heatmap,
+}
+
+fn line_text(trace: &LineTrace) -> String {
+    trace.moves.iter().map(|m| m.direction.letter()).collect()
+}
+
+// The finger's key in brackets
+fn frame(keypad: &Keypad, at: Key, caption: &str) -> String {
+    let mut frame = keypad.draw(|k| {
+        if k == at {
+            format!("[{}]", keypad.label(k))
+        } else {
+            keypad.label(k).to_string()
+        }
+    });
+    frame.push(String::new());
+    frame.push(caption.to_string());
+    frame.join("\n")
+}
+
+// Redraws the keypad in place for every move, clearing the screen with ANSI codes
+pub fn animate(keypad: &Keypad, traces: &[LineTrace], delay: Duration) -> io::Result<()> {
+    let stdout = io::stdout();
+    let mut out = stdout.lock();
+    let mut code = String::new();
+
+    for (i, trace) in traces.iter().enumerate() {
+        let start = format!("line {}: starting on {}", i + 1, keypad.label(trace.start));
+        writeln!(out, "\x1b[2J\x1b[H{}", frame(keypad, trace.start, &start))?;
+        out.flush()?;
+        thread::sleep(delay);
+
+        for (j, m) in trace.moves.iter().enumerate() {
+            let caption = format!("line {}, move {} of {}: {}{}", i + 1, j + 1, trace.moves.len(),
+                m.direction.letter(), if m.blocked { " (blocked)" } else { "" });
+            writeln!(out, "\x1b[2J\x1b[H{}", frame(keypad, m.key, &caption))?;
+            out.flush()?;
+            thread::sleep(delay);
+        }
+
+        code.push(keypad.label(trace.end));
+    }
+
+    writeln!(out, "code {}", code)
+}
+
+// How often each key was under the finger during each line, counting the
+// key the line starts on and a blocked move as another visit
+pub fn heatmap(keypad: &Keypad, traces: &[LineTrace]) -> Vec<String> {
+    let mut out = Vec::new();
+
+    for (i, trace) in traces.iter().enumerate() {
+        let mut visits: HashMap<Key, usize> = HashMap::new();
+        *visits.entry(trace.start).or_insert(0) += 1;
+        for m in &trace.moves {
+            *visits.entry(m.key).or_insert(0) += 1;
+        }
+
+        if i > 0 {
+            out.push(String::new());
+        }
+        out.push(format!("line {}: {} -> {}", i + 1, line_text(trace), keypad.label(trace.end)));
+        out.extend(keypad.draw(|k| match visits.get(&k) {
+            Some(n) => format!("{}:{}", keypad.label(k), n),
+            None => format!("{}:.", keypad.label(k)),
+        }));
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use day2::{trace, SQUARE};
+
+    #[test]
+    fn heatmap_counts_visits() {
+        let square = Keypad::new(SQUARE, '5').unwrap();
+        let traces = trace(&square, "ULL\nRR").unwrap();
+        assert_eq!(heatmap(&square, &traces), vec![
+            "line 1: ULL -> 1",
+            " 1:2  2:1  3:.",
+            " 4:.  5:1  6:.",
+            " 7:.  8:.  9:.",
+            "",
+            "line 2: RR -> 3",
+            " 1:1  2:1  3:1",
+            " 4:.  5:.  6:.",
+            " 7:.  8:.  9:.",
+        ]);
+    }
+
+    #[test]
+    fn frame_marks_finger() {
+        let square = Keypad::new(SQUARE, '5').unwrap();
+        assert_eq!(frame(&square, square.start(), "start"),
+            "  1    2    3\n  4   [5]   6\n  7    8    9\n\nstart");
+    }
+}
@@ -1,8 +1,385 @@
 extern crate crypto;
 
+use std::env;
+use std::fmt::Display;
+use std::fs;
+use std::io::{self, BufRead, BufReader, Cursor, Write};
+use std::path::PathBuf;
+use std::process;
+use std::time::Duration;
+
+mod answers;
+mod bench;
+mod cli;
+mod columns;
+mod registry;
+mod solver;
 mod get_input;
+mod keypad_trace;
+mod parse_error;
+mod path_export;
+mod path_stats;
+mod profile;
+mod provider;
+mod run_all;
+mod segments;
+mod timing;
+mod triangle_batch;
+mod triangle_report;
+mod turtle;
+mod verify;
+mod day1;
+mod day2;
+mod day3;
+mod day4;
+mod day5;
 mod day6;
 
+use answers::Answers;
+use bench::Format;
+use cli::{Command, KeypadMode, PathOutputs};
+use columns::Grouping;
+use get_input::{get_input, Source};
+use profile::{Profile, Profiles};
+use provider::Provider;
+use registry::Day;
+use segments::Engine;
+use solver::Part;
+use turtle::Grammar;
+
+fn exit_with_error<E: Display>(e: E) -> ! {
+    eprintln!("error: {}", e);
+    process::exit(1);
+}
+
+fn find_day(day: u32) -> &'static Day {
+    match registry::find(day) {
+        Some(entry) => entry,
+        None => exit_with_error(format!("no solver for day {}", day)),
+    }
+}
+
+fn parts_or_both(part: Option<Part>) -> Vec<Part> {
+    match part {
+        Some(part) => vec![part],
+        None => vec![Part::One, Part::Two],
+    }
+}
+
+fn resolve_profiles(profiles: Profiles) -> Vec<Profile> {
+    match profiles {
+        Profiles::One(profile) => vec![profile],
+        Profiles::All => Profile::all().unwrap_or_else(|e| {
+            exit_with_error(format!("could not list profiles: {}", e))
+        }),
+    }
+}
+
+fn load_input(day: u32, input: Option<Source>, profile: &Profile) -> String {
+    let input = match input {
+        Some(source) => get_input(day, &source),
+        None => Provider::from_env(profile).input(day),
+    };
+    input.unwrap_or_else(|e| exit_with_error(e))
+}
+
+fn run(day: u32, part: Option<Part>, input: Option<Source>, profile: Profile) {
+    let entry = find_day(day);
+    let input = load_input(day, input, &profile);
+
+    let parts = parts_or_both(part);
+
+    for part in parts {
+        let answer = entry.solver.solve(part, &input).unwrap_or_else(|e| {
+            exit_with_error(format!("day {} part {}: {}", day, part, e))
+        });
+        if answer.is_multiline() {
+            println!("Day {} Part {}:\n{}", day, part, answer);
+        } else {
+            println!("Day {} Part {}: {}", day, part, answer);
+        }
+    }
+}
+
+fn run_all(part: Option<Part>, profile: Profile) {
+    let provider = Provider::from_env(&profile);
+    if !run_all::run_all(&days_or_all(None), &provider, &parts_or_both(part)) {
+        process::exit(1);
+    }
+}
+
+fn verify(day: Option<u32>, answers: Option<String>, profiles: Profiles) {
+    let profiles = resolve_profiles(profiles);
+    let days = days_or_all(day);
+    let mut ok = true;
+
+    for (i, profile) in profiles.iter().enumerate() {
+        if profiles.len() > 1 {
+            println!("{}profile {}", if i > 0 { "\n" } else { "" }, profile);
+        }
+
+        let path = answers.as_ref().map_or_else(|| profile.answers_path(), PathBuf::from);
+        match Answers::load(&path) {
+            Ok(answers) => ok &= verify::verify(&days, &Provider::from_env(profile), &answers),
+            Err(e) => {
+                eprintln!("error: {}", e);
+                ok = false;
+            },
+        }
+    }
+
+    if !ok {
+        process::exit(1);
+    }
+}
+
+// "-" writes to stdout, like --input - reads stdin
+fn write_output(path: &str, contents: &str) {
+    let written = if path == "-" {
+        io::stdout().write_all(contents.as_bytes())
+    } else {
+        fs::write(path, contents)
+    };
+    if let Err(e) = written {
+        exit_with_error(format!("could not write {}: {}", path, e));
+    }
+}
+
+fn path(input: Option<Source>, profile: Profile, outputs: PathOutputs) {
+    let input = load_input(1, input, &profile);
+    let movements = day1::parse_movements(&input).unwrap_or_else(|e| {
+        exit_with_error(format!("day 1: {}", e))
+    });
+    let corners = day1::corners(&movements);
+
+    if outputs.stats {
+        println!("{}", path_stats::stats(&movements));
+    }
+    if outputs.map {
+        let (width, height) = outputs.size.unwrap_or_else(path_stats::terminal_size);
+        for row in path_stats::render(&movements, width, height) {
+            println!("{}", row);
+        }
+    }
+    if let Some(path) = outputs.logo {
+        write_output(&path, &path_export::logo(&movements));
+    }
+    if let Some(path) = outputs.svg {
+        write_output(&path, &path_export::svg(&corners, segments::first_revisit(Engine::Sweep, &movements)));
+    }
+    if let Some(path) = outputs.csv {
+        write_output(&path, &path_export::csv(&corners));
+    }
+}
+
+fn walk(input: Option<Source>, profile: Profile, grammar: Grammar) {
+    let input = load_input(1, input, &profile);
+    let instructions = turtle::parse(&input, grammar).unwrap_or_else(|e| {
+        exit_with_error(format!("day 1: {}", e))
+    });
+    let walked = turtle::walk(&instructions);
+
+    println!("end {} ({} away)", walked.end, walked.end.distance());
+    match walked.first_revisit {
+        Some(p) => println!("first revisit {} ({} away)", p, p.distance()),
+        None => println!("first revisit none"),
+    }
+}
+
+fn load_layout(layout: &str) -> String {
+    match layout {
+        "square" => day2::SQUARE.to_string(),
+        "diamond" => day2::DIAMOND.to_string(),
+        path => fs::read_to_string(path).unwrap_or_else(|e| {
+            exit_with_error(format!("could not read keypad layout {}: {}", path, e))
+        }),
+    }
+}
+
+fn reach(keypad: &day2::Keypad, code: Option<String>) {
+    println!("key  route from {}", keypad.label(keypad.start()));
+    for key in keypad.keys() {
+        let route = match keypad.route(keypad.start(), key) {
+            Some(ref r) if r.is_empty() => "start".to_string(),
+            Some(r) => r,
+            None => "unreachable".to_string(),
+        };
+        println!("{:>3}  {}", keypad.label(key), route);
+    }
+    println!("{} of {} keys reachable", keypad.reachable().len(), keypad.keys().len());
+
+    if let Some(code) = code {
+        let lines = keypad.type_code(&code).unwrap_or_else(|e| {
+            exit_with_error(format!("can't type {}: {}", code, e))
+        });
+        let lines: Vec<&str> = lines.iter().map(|l| if l.is_empty() { "-" } else { l.as_str() }).collect();
+        println!("{}: {}", code, lines.join(" "));
+    }
+}
+
+// Solving the generated input again checks the forward and reverse
+// engines against each other
+fn for_code(keypad: &day2::Keypad, code: &str) {
+    let input = day2::instructions_for(keypad, code).unwrap_or_else(|e| {
+        exit_with_error(format!("can't type {}: {}", code, e))
+    });
+    match day2::door_code(keypad, &input) {
+        Ok(ref typed) if typed == code => print!("{}", input),
+        Ok(typed) => exit_with_error(format!("instructions for {} type {} instead", code, typed)),
+        Err(e) => exit_with_error(format!("instructions for {} don't parse: {}", code, e)),
+    }
+}
+
+fn trace(keypad: &day2::Keypad, input: &str, style: keypad_trace::Style) {
+    let traces = day2::trace(keypad, input).unwrap_or_else(|e| {
+        exit_with_error(format!("day 2: {}", e))
+    });
+    match style {
+        keypad_trace::Style::Animate { delay_ms } => {
+            if let Err(e) = keypad_trace::animate(keypad, &traces, Duration::from_millis(delay_ms)) {
+                exit_with_error(e);
+            }
+        },
+        keypad_trace::Style::Heatmap => {
+            for line in keypad_trace::heatmap(keypad, &traces) {
+                println!("{}", line);
+            }
+        },
+    }
+}
+
+fn stream(keypad: &day2::Keypad, source: Source) {
+    let reader: Box<dyn BufRead> = match source {
+        Source::Stdin => Box::new(io::stdin().lock()),
+        Source::Inline(text) => Box::new(Cursor::new(text.into_bytes())),
+        Source::Path(ref path) => match fs::File::open(path) {
+            Ok(file) => Box::new(BufReader::new(file)),
+            Err(e) => exit_with_error(format!("could not read {}: {}", path.display(), e)),
+        },
+    };
+
+    let streamed = day2::stream_code(keypad, reader, |key| {
+        print!("{}", key);
+        io::stdout().flush()
+    });
+    println!();
+    if let Err(e) = streamed {
+        exit_with_error(format!("day 2: {}", e));
+    }
+}
+
+fn keypad(input: Option<Source>, profile: Profile, layout: String, start: char, mode: KeypadMode) {
+    let keypad = day2::Keypad::new(&load_layout(&layout), start).unwrap_or_else(|e| {
+        exit_with_error(format!("keypad layout {}: {}", layout, e))
+    });
+
+    match mode {
+        KeypadMode::Reach { code } => reach(&keypad, code),
+        KeypadMode::ForCode(code) => for_code(&keypad, &code),
+        KeypadMode::Trace(style) => trace(&keypad, &load_input(2, input, &profile), style),
+        KeypadMode::Stream => stream(&keypad, input.expect("--stream requires an input")),
+        KeypadMode::Code => {
+            let input = load_input(2, input, &profile);
+            let code = day2::door_code(&keypad, &input).unwrap_or_else(|e| {
+                exit_with_error(format!("day 2: {}", e))
+            });
+            println!("{}", code);
+        },
+    }
+}
+
+fn triangles(input: Option<Source>, profile: Profile, grouping: Grouping, format: triangle_report::Format, summary: bool) {
+    let input = load_input(3, input, &profile);
+    let candidates: Result<Vec<_>, _> = day3::candidates(&input, grouping)
+        .map(|r| r.map(|s| day3::Triangle::from_sides(&s)))
+        .collect();
+    let candidates = candidates.unwrap_or_else(|e| exit_with_error(format!("day 3: {}", e)));
+
+    let lines = if summary {
+        triangle_report::summary(&candidates)
+    } else {
+        triangle_report::rows(&candidates, format)
+    };
+    for line in lines {
+        println!("{}", line);
+    }
+}
+
+fn rooms(input: Option<Source>, profile: Profile) {
+    let input = load_input(4, input, &profile);
+    let rooms = day4::rooms(&input).unwrap_or_else(|e| exit_with_error(format!("day 4: {}", e)));
+    for (id, name) in rooms {
+        println!("{}: {}", id, name);
+    }
+}
+
+fn days_or_all(day: Option<u32>) -> Vec<&'static Day> {
+    match day {
+        Some(day) => vec![find_day(day)],
+        None => registry::DAYS.iter().collect(),
+    }
+}
+
+fn bench(day: Option<u32>, part: Option<Part>, runs: usize, format: Format, profiles: Profiles) {
+    let parts = parts_or_both(part);
+    let days = days_or_all(day);
+    let mut stats = Vec::new();
+    let mut ok = true;
+
+    for profile in resolve_profiles(profiles) {
+        let provider = Provider::from_env(&profile);
+
+        for &entry in &days {
+            let input = match provider.input(entry.day) {
+                Ok(input) => input,
+                Err(e) => {
+                    eprintln!("error: profile {}: {}", profile, e);
+                    ok = false;
+                    continue;
+                },
+            };
+
+            for &part in &parts {
+                match bench::measure(&profile, entry, part, &input, runs) {
+                    Ok(s) => stats.push(s),
+                    Err(e) => {
+                        eprintln!("error: profile {}: day {} part {}: {}", profile, entry.day, part, e);
+                        ok = false;
+                    },
+                }
+            }
+        }
+    }
+
+    bench::report(&stats, format);
+    if !ok {
+        process::exit(1);
+    }
+}
+
 fn main() {
-    day6::solve();
+    let command = match cli::parse(env::args().skip(1)) {
+        Ok(command) => command,
+        Err(e) => {
+            eprintln!("error: {}\n\n{}", e, cli::USAGE);
+            process::exit(2);
+        },
+    };
+
+    match command {
+        Command::Run { day, part, input, profile } => run(day, part, input, profile),
+        Command::RunAll { part, profile } => run_all(part, profile),
+        Command::Verify { day, answers, profiles } => verify(day, answers, profiles),
+        Command::Path { input, profile, outputs } => path(input, profile, outputs),
+        Command::Walk { input, profile, grammar } => walk(input, profile, grammar),
+        Command::Keypad { input, profile, layout, start, mode } => keypad(input, profile, layout, start, mode),
+        Command::Triangles { input, profile, grouping, format, summary } =>
+            triangles(input, profile, grouping, format, summary),
+        Command::Rooms { input, profile } => rooms(input, profile),
+        Command::Crossings { moves, length, runs, engines } =>
+            segments::compare(moves, length as i32, runs, &engines),
+        Command::Validate { rows, groupings, runs, engines } =>
+            triangle_batch::compare(rows, runs, &groupings, &engines),
+        Command::Bench { day, part, runs, format, profiles } => bench(day, part, runs, format, profiles),
+    }
 }
@@ -0,0 +1,96 @@
+use std::error;
+use std::fmt;
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: usize,   // 1-based
+    pub column: usize, // 1-based, in chars
+    pub token: String,
+    pub expected: String,
+    source_line: String,
+}
+
+impl ParseError {
+    // `token` must be a slice of `input` so its position can be recovered.
+    // Point at missing tokens with an empty slice, e.g. `&line[line.len()..]`.
+    pub fn at(input: &str, token: &str, expected: &str) -> ParseError {
+        let offset = token.as_ptr() as usize - input.as_ptr() as usize;
+        assert!(offset <= input.len(), "token is not a slice of input");
+
+        let line_start = input[..offset].rfind('\n').map_or(0, |i| i + 1);
+        let line_end = input[offset..].find('\n').map_or(input.len(), |i| offset + i);
+
+        ParseError {
+            line: input[..line_start].matches('\n').count() + 1,
+            column: input[line_start..offset].chars().count() + 1,
+            token: token.to_string(),
+            expected: expected.to_string(),
+            // any other control character is blanked so the caret still lines up
+            source_line: input[line_start..line_end].trim_end_matches('\r').chars()
+                .map(|c| if c.is_control() && c != '\t' { ' ' } else { c })
+                .collect(),
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.token.is_empty() {
+            writeln!(f, "line {}, column {}: expected {}", self.line, self.column, self.expected)?;
+        } else {
+            // a stray \r or tab would be invisible, or worse, between the quotes
+            let token: String = self.token.chars()
+                .map(|c| if c.is_control() { c.escape_default().to_string() } else { c.to_string() })
+                .collect();
+            writeln!(f, "line {}, column {}: expected {}, found '{}'",
+                self.line, self.column, self.expected, token)?;
+        }
+
+        let gutter = " ".repeat(self.line.to_string().len());
+        let carets = "^".repeat(self.token.chars().count().max(1));
+        writeln!(f, "{} |", gutter)?;
+        writeln!(f, "{} | {}", self.line, self.source_line)?;
+        write!(f, "{} | {}{}", gutter, " ".repeat(self.column - 1), carets)
+    }
+}
+
+impl error::Error for ParseError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_snippet() {
+        let input = "first\nsecond 12x\n";
+        let e = ParseError::at(input, &input[13..16], "a number");
+        assert_eq!((e.line, e.column, e.token.as_str()), (2, 8, "12x"));
+        assert_eq!(e.to_string(), "\
+line 2, column 8: expected a number, found '12x'
+  |
+2 | second 12x
+  |        ^^^");
+    }
+
+    #[test]
+    fn escapes_control_characters() {
+        let input = "U\rD\n";
+        let e = ParseError::at(input, &input[1..2], "a direction");
+        assert_eq!(e.to_string(), "\
+line 1, column 2: expected a direction, found '\\r'
+  |
+1 | U D
+  |  ^");
+    }
+
+    #[test]
+    fn renders_missing_token() {
+        let input = "abc\r\n";
+        let e = ParseError::at(input, &input[3..3], "more");
+        assert_eq!(e.to_string(), "\
+line 1, column 4: expected more
+  |
+1 | abc
+  |    ^");
+    }
+}
@@ -0,0 +1,95 @@
+use std::cmp;
+use std::fmt::Write;
+
+use day1::{Position, Rotation};
+
+// Logo turtles start at the origin facing up, just like day 1's walker
+pub fn logo(movements: &[(Rotation, i32)]) -> String {
+    let mut program = String::from("; advent of code 2016 day 1\n");
+    for &(rotation, distance) in movements {
+        let turn = match rotation {
+            Rotation::Left => "lt",
+            Rotation::Right => "rt",
+        };
+        writeln!(program, "{} 90 fd {}", turn, distance).unwrap();
+    }
+    program
+}
+
+fn marker(svg: &mut String, p: Position, r: i64, colour: &str, label: &str) {
+    writeln!(svg, "  <circle cx=\"{}\" cy=\"{}\" r=\"{}\" fill=\"{}\"><title>{} {}, {}</title></circle>",
+        p.x, -p.y, r, colour, label, p.x, p.y).unwrap();
+}
+
+// SVG's y axis points down, so north is flipped to -y
+pub fn svg(corners: &[Position], crossing: Option<Position>) -> String {
+    let min_x = corners.iter().map(|p| p.x).min().unwrap_or(0);
+    let max_x = corners.iter().map(|p| p.x).max().unwrap_or(0);
+    let min_y = corners.iter().map(|p| p.y).min().unwrap_or(0);
+    let max_y = corners.iter().map(|p| p.y).max().unwrap_or(0);
+    let margin = cmp::max(max_x - min_x, max_y - min_y) / 20 + 1;
+
+    let mut svg = String::new();
+    writeln!(svg, "<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"{} {} {} {}\">",
+        min_x - margin, -max_y - margin,
+        max_x - min_x + 2 * margin, max_y - min_y + 2 * margin).unwrap();
+
+    let points = corners.iter()
+        .map(|p| format!("{},{}", p.x, -p.y))
+        .collect::<Vec<_>>()
+        .join(" ");
+    writeln!(svg, "  <polyline points=\"{}\" fill=\"none\" stroke=\"black\" \
+        stroke-width=\"1\" vector-effect=\"non-scaling-stroke\"/>", points).unwrap();
+
+    let r = cmp::max(margin / 2, 1);
+    if let (Some(&start), Some(&end)) = (corners.first(), corners.last()) {
+        marker(&mut svg, start, r, "green", "start");
+        marker(&mut svg, end, r, "red", "end");
+    }
+    if let Some(crossing) = crossing {
+        marker(&mut svg, crossing, r, "blue", "first crossing");
+    }
+
+    svg.push_str("</svg>\n");
+    svg
+}
+
+pub fn csv(corners: &[Position]) -> String {
+    let mut csv = String::from("x,y\n");
+    for p in corners {
+        writeln!(csv, "{},{}", p.x, p.y).unwrap();
+    }
+    csv
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use day1::{corners, first_revisit, parse_movements};
+
+    const EXAMPLE: &str = "R8, R4, R4, R8";
+
+    #[test]
+    fn logo_program() {
+        let movements = parse_movements(EXAMPLE).unwrap();
+        assert_eq!(logo(&movements),
+            "; advent of code 2016 day 1\nrt 90 fd 8\nrt 90 fd 4\nrt 90 fd 4\nrt 90 fd 8\n");
+    }
+
+    #[test]
+    fn csv_vertices() {
+        let movements = parse_movements(EXAMPLE).unwrap();
+        assert_eq!(csv(&corners(&movements)), "x,y\n0,0\n8,0\n8,-4\n4,-4\n4,4\n");
+    }
+
+    #[test]
+    fn svg_highlights_points() {
+        let movements = parse_movements(EXAMPLE).unwrap();
+        let svg = svg(&corners(&movements), first_revisit(&movements));
+        assert!(svg.contains("viewBox=\"-1 -5 10 10\""));
+        assert!(svg.contains("points=\"0,0 8,0 8,4 4,4 4,-4\""));
+        assert!(svg.contains("<title>start 0, 0</title>"));
+        assert!(svg.contains("<title>end 4, 4</title>"));
+        assert!(svg.contains("<title>first crossing 4, 0</title>"));
+    }
+}
@@ -0,0 +1,145 @@
+use std::cmp;
+use std::env;
+use std::fmt;
+
+use day1::{self, Position, Rotation};
+use segments::{self, Engine};
+
+pub struct Stats {
+    pub min: Position,
+    pub max: Position,
+    pub travelled: i64,
+    pub end: Position,
+    pub crossings: usize,
+    pub first_crossing: Option<Position>,
+}
+
+fn bounds(corners: &[Position]) -> (Position, Position) {
+    let min = Position {
+        x: corners.iter().map(|p| p.x).min().unwrap_or(0),
+        y: corners.iter().map(|p| p.y).min().unwrap_or(0),
+    };
+    let max = Position {
+        x: corners.iter().map(|p| p.x).max().unwrap_or(0),
+        y: corners.iter().map(|p| p.y).max().unwrap_or(0),
+    };
+    (min, max)
+}
+
+// Crossings come from the segment sweep, so huge moves are fine here
+pub fn stats(movements: &[(Rotation, i32)]) -> Stats {
+    let corners = day1::corners(movements);
+    let (min, max) = bounds(&corners);
+
+    Stats {
+        min,
+        max,
+        travelled: movements.iter().map(|&(_, d)| d as i64).sum(),
+        end: corners[corners.len() - 1],
+        crossings: segments::crossings(movements),
+        first_crossing: segments::first_revisit(Engine::Sweep, movements),
+    }
+}
+
+impl fmt::Display for Stats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "bounding box    {}, {} to {}, {} ({} x {} blocks)",
+            self.min.x, self.min.y, self.max.x, self.max.y,
+            self.max.x - self.min.x + 1, self.max.y - self.min.y + 1)?;
+        writeln!(f, "travelled       {} blocks", self.travelled)?;
+        writeln!(f, "end             {}, {} ({} away)", self.end.x, self.end.y, self.end.distance())?;
+        writeln!(f, "crossings       {}", self.crossings)?;
+        match self.first_crossing {
+            Some(p) => write!(f, "first crossing  {}, {} ({} away)", p.x, p.y, p.distance()),
+            None => write!(f, "first crossing  none"),
+        }
+    }
+}
+
+// COLUMNS and LINES are only seen when the shell exports them,
+// and a line is left over for the prompt
+pub fn terminal_size() -> (usize, usize) {
+    let var = |name, default| env::var(name).ok()
+        .and_then(|v| v.parse::<usize>().ok())
+        .unwrap_or(default);
+    (cmp::max(var("COLUMNS", 80), 1), cmp::max(var("LINES", 24), 2) - 1)
+}
+
+// Each character covers scale x scale blocks, the same both ways so the map
+// isn't stretched. North is up; S, E and X mark start, end and first crossing.
+pub fn render(movements: &[(Rotation, i32)], width: usize, height: usize) -> Vec<String> {
+    let corners = day1::corners(movements);
+    let (min, max) = bounds(&corners);
+    let span_x = (max.x - min.x) as usize + 1;
+    let span_y = (max.y - min.y) as usize + 1;
+    let scale = cmp::max(cmp::max(span_x.div_ceil(width), span_y.div_ceil(height)), 1);
+
+    let mut grid = vec![vec![' '; span_x.div_ceil(scale)]; span_y.div_ceil(scale)];
+    {
+        let mut plot = |p: Position, c: char| {
+            grid[(max.y - p.y) as usize / scale][(p.x - min.x) as usize / scale] = c;
+        };
+
+        for w in corners.windows(2) {
+            let (dx, dy) = ((w[1].x - w[0].x).signum(), (w[1].y - w[0].y).signum());
+            let len = ((w[1].x - w[0].x).abs() + (w[1].y - w[0].y).abs()) as usize;
+            for k in (0..len).step_by(scale) {
+                let k = k as i64;
+                plot(Position { x: w[0].x + k * dx, y: w[0].y + k * dy }, '#');
+            }
+            plot(w[1], '#');
+        }
+
+        plot(corners[0], 'S');
+        plot(corners[corners.len() - 1], 'E');
+        if let Some(p) = segments::first_revisit(Engine::Sweep, movements) {
+            plot(p, 'X');
+        }
+    }
+
+    grid.into_iter()
+        .map(|row| row.into_iter().collect::<String>().trim_end().to_string())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use day1::parse_movements;
+
+    const EXAMPLE: &str = "R8, R4, R4, R8";
+
+    #[test]
+    fn example_stats() {
+        let s = stats(&parse_movements(EXAMPLE).unwrap());
+        assert_eq!((s.min, s.max), (Position { x: 0, y: -4 }, Position { x: 8, y: 4 }));
+        assert_eq!((s.travelled, s.end.distance(), s.crossings), (24, 8, 1));
+        assert_eq!(s.first_crossing, Some(Position { x: 4, y: 0 }));
+    }
+
+    #[test]
+    fn renders_full_size() {
+        let map = render(&parse_movements(EXAMPLE).unwrap(), 80, 24);
+        assert_eq!(map, vec![
+            "    E",
+            "    #",
+            "    #",
+            "    #",
+            "S###X####",
+            "    #   #",
+            "    #   #",
+            "    #   #",
+            "    #####",
+        ]);
+    }
+
+    #[test]
+    fn renders_scaled_down() {
+        let map = render(&parse_movements(EXAMPLE).unwrap(), 3, 3);
+        assert_eq!(map, vec![
+            " E",
+            "SX#",
+            " ##",
+        ]);
+    }
+}
@@ -0,0 +1,103 @@
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+const ASSETS_DIR: &str = "assets";
+
+// Whose puzzle inputs and answers to use. The default profile lives directly
+// in assets/, a named one in assets/<name>/ with the same layout:
+//
+//     assets/<name>/day/N/input
+//     assets/<name>/answers.toml
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Profile {
+    name: Option<String>,
+}
+
+impl Profile {
+    pub fn default_profile() -> Profile {
+        Profile { name: None }
+    }
+
+    // None if the name could escape assets/ or clash with its layout
+    pub fn named(name: &str) -> Option<Profile> {
+        let valid = !name.is_empty()
+            && name != "day"
+            && !name.starts_with('.')
+            && name.chars().all(|c| c.is_alphanumeric() || c == '-' || c == '_' || c == '.');
+        if valid {
+            Some(Profile { name: Some(name.to_string()) })
+        } else {
+            None
+        }
+    }
+
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    pub fn dir(&self) -> PathBuf {
+        match self.name {
+            Some(ref name) => PathBuf::from(ASSETS_DIR).join(name),
+            None => PathBuf::from(ASSETS_DIR),
+        }
+    }
+
+    pub fn answers_path(&self) -> PathBuf {
+        self.dir().join("answers.toml")
+    }
+
+    // The default profile followed by every assets/<name>/ that has inputs
+    // or answers, sorted by name
+    pub fn all() -> io::Result<Vec<Profile>> {
+        let mut names = Vec::new();
+        for entry in fs::read_dir(ASSETS_DIR)? {
+            let path = entry?.path();
+            let name = match path.file_name().and_then(|n| n.to_str()) {
+                Some(name) => name.to_string(),
+                None => continue,
+            };
+            if path.is_dir() && (path.join("day").is_dir() || path.join("answers.toml").is_file()) {
+                if let Some(profile) = Profile::named(&name) {
+                    names.push(profile);
+                }
+            }
+        }
+        names.sort_by(|a, b| a.name.cmp(&b.name));
+
+        let mut profiles = vec![Profile::default_profile()];
+        profiles.extend(names);
+        Ok(profiles)
+    }
+}
+
+impl fmt::Display for Profile {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.pad(self.name.as_ref().map_or("default", |n| n.as_str()))
+    }
+}
+
+pub enum Profiles {
+    One(Profile),
+    All,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn profile_dirs() {
+        assert_eq!(Profile::default_profile().dir(), PathBuf::from("assets"));
+        assert_eq!(Profile::named("alice").unwrap().answers_path(),
+                   PathBuf::from("assets/alice/answers.toml"));
+    }
+
+    #[test]
+    fn rejects_names_outside_assets() {
+        for name in &["", "..", "../bob", "a/b", "day", ".hidden"] {
+            assert_eq!(Profile::named(name), None, "{}", name);
+        }
+    }
+}
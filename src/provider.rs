@@ -0,0 +1,325 @@
+use std::env;
+use std::error;
+use std::fmt;
+use std::fs;
+use std::io::{self, Read, Write};
+use std::net::TcpStream;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+
+use get_input::{get_input, InputError, Source};
+use profile::Profile;
+
+pub const DEFAULT_BASE_URL: &str = "https://adventofcode.com";
+const USER_AGENT: &str = "github.com/ianwjhalliday/adventofcode2016";
+
+#[derive(Debug)]
+pub enum FetchError {
+    Offline,
+    NoSession,
+    BadUrl(String),
+    Http(u32),
+    EmptyBody,
+    Io(io::Error),
+    Backend(String),
+}
+
+impl fmt::Display for FetchError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            FetchError::Offline => write!(f, "fetching is disabled (AOC_OFFLINE is set)"),
+            FetchError::NoSession =>
+                write!(f, "no session cookie, set AOC_SESSION (AOC_SESSION_<NAME> for a profile)"),
+            FetchError::BadUrl(ref url) => write!(f, "cannot fetch {}", url),
+            FetchError::Http(status) => write!(f, "server responded with HTTP {}", status),
+            FetchError::EmptyBody => write!(f, "server sent an empty input"),
+            FetchError::Io(ref e) => write!(f, "{}", e),
+            FetchError::Backend(ref msg) => write!(f, "{}", msg),
+        }
+    }
+}
+
+impl error::Error for FetchError {}
+
+impl From<io::Error> for FetchError {
+    fn from(e: io::Error) -> FetchError {
+        FetchError::Io(e)
+    }
+}
+
+pub trait HttpBackend {
+    fn get(&self, url: &str, session: Option<&str>) -> Result<String, FetchError>;
+}
+
+// Plain http:// over a TcpStream, enough to talk to a local mirror or mock
+pub struct TcpBackend;
+
+impl HttpBackend for TcpBackend {
+    fn get(&self, url: &str, session: Option<&str>) -> Result<String, FetchError> {
+        let session = session.ok_or(FetchError::NoSession)?;
+        let rest = url.strip_prefix("http://").ok_or_else(|| FetchError::BadUrl(url.to_string()))?;
+        let (host, path) = match rest.find('/') {
+            Some(i) => (&rest[..i], &rest[i..]),
+            None => (rest, "/"),
+        };
+        let addr = if host.contains(':') { host.to_string() } else { format!("{}:80", host) };
+
+        // HTTP/1.0 so the body is never chunked, and ends when the server closes
+        let mut stream = TcpStream::connect(addr)?;
+        write!(stream, "GET {} HTTP/1.0\r\nHost: {}\r\nUser-Agent: {}\r\nCookie: session={}\r\n\r\n",
+            path, host, USER_AGENT, session)?;
+        let mut response = Vec::new();
+        stream.read_to_end(&mut response)?;
+        let response = String::from_utf8_lossy(&response);
+
+        let status = response.split_whitespace().nth(1)
+            .and_then(|s| s.parse::<u32>().ok())
+            .ok_or_else(|| FetchError::Backend("malformed HTTP response".to_string()))?;
+        if status != 200 {
+            return Err(FetchError::Http(status));
+        }
+        match response.find("\r\n\r\n") {
+            Some(i) => Ok(response[i + 4..].to_string()),
+            None => Err(FetchError::Backend("HTTP response has no body".to_string())),
+        }
+    }
+}
+
+// https:// by shelling out to curl rather than pulling in a TLS stack. The
+// session goes to curl as a config file on stdin, since anyone on the machine
+// can read its command line.
+pub struct CurlBackend;
+
+impl HttpBackend for CurlBackend {
+    fn get(&self, url: &str, session: Option<&str>) -> Result<String, FetchError> {
+        let session = session.ok_or(FetchError::NoSession)?;
+        let config = format!("cookie = \"session={}\"\n", session.replace('\\', "\\\\").replace('"', "\\\""));
+        let run = || -> io::Result<_> {
+            let mut curl = Command::new("curl")
+                .args(["--silent", "--show-error", "--fail", "--user-agent", USER_AGENT, "--config", "-"])
+                .arg(url)
+                .stdin(Stdio::piped())
+                .stdout(Stdio::piped())
+                .stderr(Stdio::piped())
+                .spawn()?;
+            // dropped straight after writing, so curl sees the end of its config
+            curl.stdin.take().expect("stdin is piped").write_all(config.as_bytes())?;
+            curl.wait_with_output()
+        };
+        let output = run().map_err(|e| FetchError::Backend(format!("could not run curl: {}", e)))?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(FetchError::Backend(stderr.trim().to_string()));
+        }
+        String::from_utf8(output.stdout)
+            .map_err(|_| FetchError::Backend("response is not valid UTF-8".to_string()))
+    }
+}
+
+// Stand-in for machines without network access: only the cache is used
+pub struct Offline;
+
+impl HttpBackend for Offline {
+    fn get(&self, _url: &str, _session: Option<&str>) -> Result<String, FetchError> {
+        Err(FetchError::Offline)
+    }
+}
+
+// AOC_OFFLINE=1 and the like; empty or 0 leaves fetching on
+fn offline(value: Option<&str>) -> bool {
+    matches!(value, Some(v) if !v.is_empty() && v != "0")
+}
+
+// Finds puzzle inputs in the local cache, <cache_dir>/day/N/input, and on a
+// miss fetches them from <base_url>/2016/day/N/input into the cache.
+pub struct Provider {
+    cache_dir: PathBuf,
+    base_url: String,
+    session: Option<String>,
+    backend: Box<dyn HttpBackend + Send + Sync>,
+}
+
+impl Provider {
+    pub fn new(cache_dir: PathBuf, base_url: &str, session: Option<String>,
+               backend: Box<dyn HttpBackend + Send + Sync>) -> Provider {
+        Provider {
+            cache_dir,
+            base_url: base_url.trim_end_matches('/').to_string(),
+            session,
+            backend,
+        }
+    }
+
+    // Caches into the profile's directory. Configured by AOC_SESSION,
+    // AOC_BASE_URL and AOC_OFFLINE; the backend follows the base URL's scheme.
+    // Each profile is a different account, so a named profile only fetches
+    // with its own AOC_SESSION_<NAME>, never the default session.
+    pub fn from_env(profile: &Profile) -> Provider {
+        let base_url = env::var("AOC_BASE_URL").unwrap_or_else(|_| DEFAULT_BASE_URL.to_string());
+        let session_var = match profile.name() {
+            Some(name) => format!("AOC_SESSION_{}", name.to_uppercase().replace(['-', '.'], "_")),
+            None => "AOC_SESSION".to_string(),
+        };
+        let session = env::var(session_var).ok().filter(|s| !s.is_empty());
+        let backend: Box<dyn HttpBackend + Send + Sync> = if offline(env::var("AOC_OFFLINE").ok().as_deref()) {
+            Box::new(Offline)
+        } else if base_url.starts_with("http://") {
+            Box::new(TcpBackend)
+        } else {
+            Box::new(CurlBackend)
+        };
+        Provider::new(profile.dir(), &base_url, session, backend)
+    }
+
+    pub fn cache_path(&self, day: u32) -> PathBuf {
+        self.cache_dir.join("day").join(day.to_string()).join("input")
+    }
+
+    pub fn url(&self, day: u32) -> String {
+        format!("{}/2016/day/{}/input", self.base_url, day)
+    }
+
+    fn fetch(&self, day: u32) -> Result<(), FetchError> {
+        let body = self.backend.get(&self.url(day), self.session.as_deref())?;
+        // cached, it would be read back as an empty input on every later run
+        if body.is_empty() {
+            return Err(FetchError::EmptyBody);
+        }
+        let path = self.cache_path(day);
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(&path, body)?;
+        Ok(())
+    }
+
+    pub fn input(&self, day: u32) -> Result<String, InputError> {
+        let path = self.cache_path(day);
+        if !path.exists() {
+            self.fetch(day).map_err(|error| InputError::Fetch { day, url: self.url(day), error })?;
+        }
+        get_input(day, &Source::Path(path))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::TcpListener;
+    use std::process;
+    use std::thread;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("advent2016-{}-{}", name, process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    // Answers one request with `response` and hands back the request it saw
+    fn mock_server(response: &'static str) -> (String, thread::JoinHandle<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let handle = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = Vec::new();
+            let mut buf = [0; 1024];
+            while !request.ends_with(b"\r\n\r\n") {
+                let n = stream.read(&mut buf).unwrap();
+                request.extend_from_slice(&buf[..n]);
+            }
+            stream.write_all(response.as_bytes()).unwrap();
+            String::from_utf8(request).unwrap()
+        });
+        (base_url, handle)
+    }
+
+    #[test]
+    fn fetches_into_cache_on_miss() {
+        let (base_url, server) = mock_server("HTTP/1.1 200 OK\r\nContent-Length: 7\r\n\r\nR2, L3\n");
+        let dir = temp_dir("fetch");
+        let provider = Provider::new(dir.clone(), &base_url, Some("abc".to_string()), Box::new(TcpBackend));
+
+        assert_eq!(provider.input(1).unwrap(), "R2, L3\n");
+        let request = server.join().unwrap();
+        assert!(request.starts_with("GET /2016/day/1/input HTTP/1.0\r\n"));
+        assert!(request.contains("Cookie: session=abc\r\n"));
+
+        // the server is gone, so this must come from the cache
+        assert_eq!(fs::read_to_string(dir.join("day/1/input")).unwrap(), "R2, L3\n");
+        assert_eq!(provider.input(1).unwrap(), "R2, L3\n");
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn curl_sends_session_on_stdin() {
+        if Command::new("curl").arg("--version").output().is_err() {
+            eprintln!("curl is not on PATH, skipping");
+            return;
+        }
+        let (base_url, server) = mock_server("HTTP/1.1 200 OK\r\nContent-Length: 7\r\n\r\nR2, L3\n");
+        let body = CurlBackend.get(&format!("{}/2016/day/1/input", base_url), Some("a\"b")).unwrap();
+        assert_eq!(body, "R2, L3\n");
+        assert!(server.join().unwrap().contains("Cookie: session=a\"b\r\n"));
+    }
+
+    #[test]
+    fn http_error_is_not_cached() {
+        let (base_url, server) = mock_server("HTTP/1.1 404 Not Found\r\n\r\nnope");
+        let dir = temp_dir("http-error");
+        let provider = Provider::new(dir.clone(), &base_url, Some("abc".to_string()), Box::new(TcpBackend));
+
+        match provider.input(2) {
+            Err(InputError::Fetch { day: 2, error: FetchError::Http(404), .. }) => (),
+            other => panic!("expected HTTP 404, got {:?}", other),
+        }
+        server.join().unwrap();
+        assert!(!dir.join("day/2/input").exists());
+    }
+
+    #[test]
+    fn empty_body_is_not_cached() {
+        let (base_url, server) = mock_server("HTTP/1.1 200 OK\r\nContent-Length: 0\r\n\r\n");
+        let dir = temp_dir("empty-body");
+        let provider = Provider::new(dir.clone(), &base_url, Some("abc".to_string()), Box::new(TcpBackend));
+
+        match provider.input(6) {
+            Err(InputError::Fetch { day: 6, error: FetchError::EmptyBody, .. }) => (),
+            other => panic!("expected an empty body error, got {:?}", other),
+        }
+        server.join().unwrap();
+        assert!(!dir.join("day/6/input").exists());
+    }
+
+    #[test]
+    fn offline_values() {
+        assert!(offline(Some("1")));
+        assert!(offline(Some("yes")));
+        assert!(!offline(Some("0")));
+        assert!(!offline(Some("")));
+        assert!(!offline(None));
+    }
+
+    #[test]
+    fn offline_uses_cache_only() {
+        let dir = temp_dir("offline");
+        fs::create_dir_all(dir.join("day/3")).unwrap();
+        fs::write(dir.join("day/3/input"), "5 10 25\n").unwrap();
+        let provider = Provider::new(dir.clone(), DEFAULT_BASE_URL, None, Box::new(Offline));
+
+        assert_eq!(provider.input(3).unwrap(), "5 10 25\n");
+        match provider.input(4) {
+            Err(InputError::Fetch { error: FetchError::Offline, .. }) => (),
+            other => panic!("expected offline error, got {:?}", other),
+        }
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn fetch_needs_session() {
+        let provider = Provider::new(temp_dir("session"), "http://127.0.0.1:1", None, Box::new(TcpBackend));
+        match provider.input(1) {
+            Err(InputError::Fetch { error: FetchError::NoSession, .. }) => (),
+            other => panic!("expected missing session error, got {:?}", other),
+        }
+    }
+}
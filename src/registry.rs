@@ -0,0 +1,28 @@
+use day1;
+use day2;
+use day3;
+use day4;
+use day5;
+use day6;
+use solver::Solver;
+
+pub struct Day {
+    pub day: u32,
+    pub solver: &'static (dyn Solver + Sync),
+    // false for solvers that already spread across every core, so run --all
+    // runs them on their own rather than alongside the other days
+    pub concurrent: bool,
+}
+
+pub static DAYS: &[Day] = &[
+    Day { day: 1, solver: &day1::Day1, concurrent: true },
+    Day { day: 2, solver: &day2::Day2, concurrent: true },
+    Day { day: 3, solver: &day3::Day3, concurrent: true },
+    Day { day: 4, solver: &day4::Day4, concurrent: true },
+    Day { day: 5, solver: &day5::Day5, concurrent: false },
+    Day { day: 6, solver: &day6::Day6, concurrent: true },
+];
+
+pub fn find(day: u32) -> Option<&'static Day> {
+    DAYS.iter().find(|d| d.day == day)
+}
@@ -0,0 +1,83 @@
+use std::thread;
+use std::time::{Duration, Instant};
+
+use get_input::InputError;
+use provider::Provider;
+use registry::Day;
+use solver::{self, Part};
+use timing::format_duration;
+
+struct Row {
+    day: u32,
+    part: Option<Part>, // None when the input itself could not be loaded
+    answer: Result<String, String>,
+    elapsed: Duration,
+}
+
+fn run_day(day: &Day, input: Result<String, InputError>, parts: &[Part]) -> Vec<Row> {
+    let input = match input {
+        Ok(input) => input,
+        Err(e) => return vec![Row {
+            day: day.day,
+            part: None,
+            answer: Err(e.to_string()),
+            elapsed: Duration::new(0, 0),
+        }],
+    };
+
+    parts.iter().map(|&part| {
+        let start = Instant::now();
+        let answer = day.solver.solve(part, &input);
+        let elapsed = start.elapsed();
+        Row {
+            day: day.day,
+            part: Some(part),
+            answer: answer.map(|a| a.brief()).map_err(|e| solver::brief_error(&e)),
+            elapsed,
+        }
+    }).collect()
+}
+
+// Solves every part of every given day, one thread per day, and prints a
+// summary table. Days not marked concurrent run afterwards on their own.
+// Inputs are loaded up front, one at a time, so a cold cache doesn't hit the
+// server with a burst of fetches. Returns false if any day failed.
+pub fn run_all(days: &[&'static Day], provider: &Provider, parts: &[Part]) -> bool {
+    let start = Instant::now();
+    let mut rows = Vec::new();
+
+    let (concurrent, alone): (Vec<&'static Day>, Vec<&'static Day>) =
+        days.iter().partition(|d| d.concurrent);
+
+    let threads: Vec<_> = concurrent.into_iter().map(|day| {
+        let input = provider.input(day.day);
+        let parts = parts.to_vec();
+        thread::spawn(move || run_day(day, input, &parts))
+    }).collect();
+    for t in threads {
+        rows.extend(t.join().expect("solver thread panicked"));
+    }
+
+    for day in alone {
+        rows.extend(run_day(day, provider.input(day.day), parts));
+    }
+
+    rows.sort_by_key(|r| (r.day, r.part.map(|p| p == Part::Two)));
+
+    let mut ok = true;
+    println!("day  part        time  answer");
+    for row in &rows {
+        let part = row.part.map_or("-".to_string(), |p| p.to_string());
+        match row.answer {
+            Ok(ref answer) =>
+                println!("{:>3}  {:>4}  {:>10}  {}", row.day, part, format_duration(row.elapsed), answer),
+            Err(ref e) => {
+                ok = false;
+                println!("{:>3}  {:>4}  {:>10}  ERROR {}", row.day, part, "-", e);
+            },
+        }
+    }
+    println!("total elapsed {}", format_duration(start.elapsed()));
+
+    ok
+}
@@ -0,0 +1,284 @@
+use std::cmp;
+use std::collections::BTreeMap;
+
+use bench::{self, Variant};
+use day1::{self, Position, Rotation};
+
+// Ways of finding day 1's first revisited location. The walk keeps every
+// block in a set, which is hopeless once moves run to millions of blocks;
+// the other two only look at the straight runs between turns.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Engine {
+    Walk,
+    Pairwise,
+    Sweep,
+}
+
+impl Variant for Engine {
+    const ALL: &'static [Engine] = &[Engine::Walk, Engine::Pairwise, Engine::Sweep];
+
+    fn name(self) -> &'static str {
+        match self {
+            Engine::Walk => "walk",
+            Engine::Pairwise => "pairwise",
+            Engine::Sweep => "sweep",
+        }
+    }
+}
+
+pub fn first_revisit(engine: Engine, movements: &[(Rotation, i32)]) -> Option<Position> {
+    match engine {
+        Engine::Walk => day1::first_revisit(movements),
+        Engine::Pairwise => pairwise(&segments(movements)),
+        Engine::Sweep => sweep(&segments(movements)),
+    }
+}
+
+// The blocks start + k * (dx, dy) for k in 1..=len; the start block was
+// already visited as the end of the previous segment
+#[derive(Copy, Clone)]
+struct Segment {
+    start: Position,
+    dx: i64,
+    dy: i64,
+    len: i64,
+}
+
+// Steps k for which from + k * d lands within lo..=hi, d being 1 or -1
+fn steps_between(from: i64, d: i64, lo: i64, hi: i64) -> (i64, i64) {
+    if d > 0 {
+        (lo - from, hi - from)
+    } else {
+        (from - hi, from - lo)
+    }
+}
+
+impl Segment {
+    fn at(&self, k: i64) -> Position {
+        Position { x: self.start.x + k * self.dx, y: self.start.y + k * self.dy }
+    }
+
+    fn is_horizontal(&self) -> bool {
+        self.dy == 0
+    }
+
+    fn x_range(&self) -> (i64, i64) {
+        let end = self.at(self.len).x;
+        (cmp::min(self.start.x, end), cmp::max(self.start.x, end))
+    }
+
+    fn y_range(&self) -> (i64, i64) {
+        let end = self.at(self.len).y;
+        (cmp::min(self.start.y, end), cmp::max(self.start.y, end))
+    }
+
+    // First step of self that lands on an earlier segment, start block included,
+    // whether they cross or run along the same line
+    fn first_hit(&self, earlier: &Segment) -> Option<i64> {
+        let (x0, x1) = earlier.x_range();
+        let (y0, y1) = earlier.y_range();
+        let (lo, hi) = if self.is_horizontal() {
+            if self.start.y < y0 || self.start.y > y1 {
+                return None;
+            }
+            steps_between(self.start.x, self.dx, x0, x1)
+        } else {
+            if self.start.x < x0 || self.start.x > x1 {
+                return None;
+            }
+            steps_between(self.start.y, self.dy, y0, y1)
+        };
+
+        let lo = cmp::max(lo, 1);
+        if lo <= cmp::min(hi, self.len) {
+            Some(lo)
+        } else {
+            None
+        }
+    }
+}
+
+// Zero length moves only turn, so they add no segment
+fn segments(movements: &[(Rotation, i32)]) -> Vec<Segment> {
+    day1::corners(movements).windows(2)
+        .filter(|w| w[0] != w[1])
+        .map(|w| Segment {
+            start: w[0],
+            dx: (w[1].x - w[0].x).signum(),
+            dy: (w[1].y - w[0].y).signum(),
+            len: (w[1].x - w[0].x).abs() + (w[1].y - w[0].y).abs(),
+        })
+        .collect()
+}
+
+// Tests every segment against all the ones before it, O(n^2)
+fn pairwise(segments: &[Segment]) -> Option<Position> {
+    for (i, s) in segments.iter().enumerate() {
+        if let Some(k) = segments[..i].iter().filter_map(|e| s.first_hit(e)).min() {
+            return Some(s.at(k));
+        }
+    }
+    None
+}
+
+// Pairs of parallel segments on the same line whose ranges overlap
+fn collinear_pairs(segments: &[Segment], horizontal: bool, pairs: &mut Vec<(usize, usize)>) {
+    let mut runs: Vec<(i64, i64, i64, usize)> = segments.iter().enumerate()
+        .filter(|&(_, s)| s.is_horizontal() == horizontal)
+        .map(|(i, s)| {
+            let ((lo, hi), line) = if horizontal {
+                (s.x_range(), s.start.y)
+            } else {
+                (s.y_range(), s.start.x)
+            };
+            (line, lo, hi, i)
+        })
+        .collect();
+    runs.sort();
+
+    let mut line = None;
+    let mut active: Vec<(i64, usize)> = Vec::new();
+    for (l, lo, hi, i) in runs {
+        if line != Some(l) {
+            active.clear();
+            line = Some(l);
+        }
+        active.retain(|&(end, _)| end >= lo);
+        for &(_, j) in &active {
+            pairs.push((cmp::min(i, j), cmp::max(i, j)));
+        }
+        active.push((hi, i));
+    }
+}
+
+// Every pair of touching segments. A vertical line sweeps left to right
+// holding the horizontal segments it currently crosses, keyed by y, so each
+// vertical segment only looks up the horizontals within its own y range.
+fn touching_pairs(segments: &[Segment]) -> Vec<(usize, usize)> {
+    const ENTER: u8 = 0;
+    const QUERY: u8 = 1;
+    const LEAVE: u8 = 2;
+
+    let mut events = Vec::with_capacity(segments.len() * 2);
+    for (i, s) in segments.iter().enumerate() {
+        let (x0, x1) = s.x_range();
+        if s.is_horizontal() {
+            events.push((x0, ENTER, i));
+            events.push((x1, LEAVE, i));
+        } else {
+            events.push((x0, QUERY, i));
+        }
+    }
+    events.sort();
+
+    let mut pairs = Vec::new();
+    let mut active: BTreeMap<i64, Vec<usize>> = BTreeMap::new();
+    for (_, event, i) in events {
+        let s = &segments[i];
+        match event {
+            ENTER => active.entry(s.start.y).or_default().push(i),
+            QUERY => {
+                let (y0, y1) = s.y_range();
+                for (_, horizontals) in active.range(y0..=y1) {
+                    for &h in horizontals {
+                        pairs.push((cmp::min(h, i), cmp::max(h, i)));
+                    }
+                }
+            },
+            _ => {
+                let empty = {
+                    let horizontals = active.get_mut(&s.start.y).unwrap();
+                    horizontals.retain(|&h| h != i);
+                    horizontals.is_empty()
+                };
+                if empty {
+                    active.remove(&s.start.y);
+                }
+            },
+        }
+    }
+
+    collinear_pairs(segments, true, &mut pairs);
+    collinear_pairs(segments, false, &mut pairs);
+    pairs
+}
+
+// Finds every touching pair up front, then keeps the hit earliest along the path
+fn sweep(segments: &[Segment]) -> Option<Position> {
+    touching_pairs(segments).into_iter()
+        .filter_map(|(a, b)| segments[b].first_hit(&segments[a]).map(|k| (b, k)))
+        .min()
+        .map(|(b, k)| segments[b].at(k))
+}
+
+// Number of times the path runs into itself, counting each pair of
+// touching moves once however long they overlap
+pub fn crossings(movements: &[(Rotation, i32)]) -> usize {
+    let segments = segments(movements);
+    touching_pairs(&segments).into_iter()
+        .filter(|&(a, b)| segments[b].first_hit(&segments[a]).is_some())
+        .count()
+}
+
+// An outward square spiral whose moves grow to about `length` blocks, so it
+// never touches itself until a final U-turn steps back onto the last move
+pub fn spiral(moves: usize, length: i32) -> Vec<(Rotation, i32)> {
+    let step = cmp::max(length / (moves as i32 / 2 + 1), 1);
+    let mut movements: Vec<_> = (0..moves)
+        .map(|i| (Rotation::Right, step * (i as i32 / 2 + 1)))
+        .collect();
+    movements.push((Rotation::Right, 0));
+    movements.push((Rotation::Right, 1));
+    movements
+}
+
+pub fn compare(moves: usize, length: i32, runs: usize, engines: &[Engine]) {
+    let movements = spiral(moves, length);
+    println!("spiral of {} moves growing to {} blocks", moves, length);
+    bench::compare(engines, runs, |engine| {
+        first_revisit(engine, &movements)
+            .map_or("none".to_string(), |p| format!("{}, {} ({} away)", p.x, p.y, p.distance()))
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use day1::parse_movements;
+
+    fn all_engines(input: &str) -> Vec<Option<Position>> {
+        let movements = parse_movements(input).unwrap();
+        bench::each(|e| first_revisit(e, &movements))
+    }
+
+    #[test]
+    fn engines_agree_on_examples() {
+        let crossing = Some(Position { x: 4, y: 0 });
+        assert_eq!(all_engines("R8, R4, R4, R8"), vec![crossing; 3]);
+        assert_eq!(all_engines("R2, L3"), vec![None; 3]);
+
+        // doubling back over a move after a zero length turn
+        let back = Some(Position { x: 3, y: 0 });
+        assert_eq!(all_engines("R4, R0, R2"), vec![back; 3]);
+
+        // passing through the origin
+        let origin = Some(Position { x: 0, y: 0 });
+        assert_eq!(all_engines("R2, R2, R2, R4"), vec![origin; 3]);
+    }
+
+    #[test]
+    fn counts_crossings() {
+        assert_eq!(crossings(&parse_movements("R8, R4, R4, R8").unwrap()), 1);
+        assert_eq!(crossings(&parse_movements("R8, R4, R4, R8, R2, R6").unwrap()), 2);
+        assert_eq!(crossings(&parse_movements("R2, L3").unwrap()), 0);
+    }
+
+    #[test]
+    fn engines_agree_on_spiral() {
+        let movements = spiral(40, 2000);
+        let walk = first_revisit(Engine::Walk, &movements);
+        assert!(walk.is_some());
+        assert_eq!(first_revisit(Engine::Pairwise, &movements), walk);
+        assert_eq!(first_revisit(Engine::Sweep, &movements), walk);
+    }
+}
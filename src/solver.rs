@@ -0,0 +1,128 @@
+use std::fmt;
+
+use parse_error::ParseError;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl Part {
+    pub fn from_number(n: u32) -> Option<Part> {
+        match n {
+            1 => Some(Part::One),
+            2 => Some(Part::Two),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Part {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let n = match *self {
+            Part::One => 1,
+            Part::Two => 2,
+        };
+        fmt::Display::fmt(&n, f)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Answer {
+    Integer(i64),
+    Text(String),
+    Grid(Vec<String>), // multi-line answers, one String per row
+}
+
+impl Answer {
+    pub fn is_multiline(&self) -> bool {
+        matches!(*self, Answer::Grid(_))
+    }
+
+    // One line summary for tables, where grid answers are too tall to fit
+    pub fn brief(&self) -> String {
+        match *self {
+            Answer::Grid(ref rows) => format!("({} rows)", rows.len()),
+            _ => self.to_string(),
+        }
+    }
+}
+
+// The same for errors: only the first line of a ParseError fits a table,
+// without its snippet
+pub fn brief_error(e: &ParseError) -> String {
+    e.to_string().lines().next().unwrap_or("").to_string()
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Answer::Integer(n) => write!(f, "{}", n),
+            Answer::Text(ref s) => write!(f, "{}", s),
+            Answer::Grid(ref rows) => write!(f, "{}", rows.join("\n")),
+        }
+    }
+}
+
+impl From<i32> for Answer {
+    fn from(n: i32) -> Answer {
+        Answer::Integer(n as i64)
+    }
+}
+
+impl From<i64> for Answer {
+    fn from(n: i64) -> Answer {
+        Answer::Integer(n)
+    }
+}
+
+impl From<u32> for Answer {
+    fn from(n: u32) -> Answer {
+        Answer::Integer(n as i64)
+    }
+}
+
+impl From<usize> for Answer {
+    fn from(n: usize) -> Answer {
+        Answer::Integer(n as i64)
+    }
+}
+
+impl From<String> for Answer {
+    fn from(s: String) -> Answer {
+        Answer::Text(s)
+    }
+}
+
+impl From<Vec<String>> for Answer {
+    fn from(rows: Vec<String>) -> Answer {
+        Answer::Grid(rows)
+    }
+}
+
+pub trait Solver {
+    fn part1(&self, input: &str) -> Result<Answer, ParseError>;
+    fn part2(&self, input: &str) -> Result<Answer, ParseError>;
+
+    fn solve(&self, part: Part, input: &str) -> Result<Answer, ParseError> {
+        match part {
+            Part::One => self.part1(input),
+            Part::Two => self.part2(input),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn brief_answers_and_errors() {
+        assert_eq!(Answer::Grid(vec!["#.".to_string(), ".#".to_string()]).brief(), "(2 rows)");
+        assert_eq!(Answer::Integer(5).brief(), "5");
+        let input = "R2, X3";
+        let e = ParseError::at(input, &input[4..6], "a movement");
+        assert_eq!(brief_error(&e), "line 1, column 5: expected a movement, found 'X3'");
+    }
+}
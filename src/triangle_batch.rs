@@ -0,0 +1,235 @@
+use bench::{self, Variant};
+use columns::{Grouping, Shape};
+use day3;
+use parse_error::ParseError;
+
+// Ways of counting day 3's valid triangles. Lines builds a Triangle from
+// each row as it's parsed, as the solver does; batch parses the whole table
+// into one buffer per side and checks them in fixed size batches, in loops
+// simple enough for the compiler to vectorize.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Engine {
+    Lines,
+    Batch,
+}
+
+impl Variant for Engine {
+    const ALL: &'static [Engine] = &[Engine::Lines, Engine::Batch];
+
+    fn name(self) -> &'static str {
+        match self {
+            Engine::Lines => "lines",
+            Engine::Batch => "batch",
+        }
+    }
+}
+
+const BATCH: usize = 256;
+
+// The candidates as structure of arrays: candidate i is (a[i], b[i], c[i])
+#[derive(Debug, Default, PartialEq)]
+pub struct Table {
+    pub a: Vec<u64>,
+    pub b: Vec<u64>,
+    pub c: Vec<u64>,
+}
+
+// Three runs of digits split by spaces or tabs, the only shape a side table
+// takes in practice. Anything else, including zero and overflowing sides,
+// is left to day 3's own parser.
+fn quick_line(line: &[u8]) -> Option<[u64; 3]> {
+    let mut sides = [0u64; 3];
+    let mut i = 0;
+    for side in &mut sides {
+        while i < line.len() && (line[i] == b' ' || line[i] == b'\t') {
+            i += 1;
+        }
+        let start = i;
+        while i < line.len() && line[i].is_ascii_digit() {
+            *side = side.checked_mul(10)?.checked_add((line[i] - b'0') as u64)?;
+            i += 1;
+        }
+        if i == start || *side == 0 {
+            return None;
+        }
+    }
+    if line[i..].iter().all(|&b| b == b' ' || b == b'\t') {
+        Some(sides)
+    } else {
+        None
+    }
+}
+
+impl Table {
+    // Fails on the same line, with the same error, as day 3's parser
+    pub fn parse(input: &str) -> Result<Table, ParseError> {
+        let rows = input.len() / 12;
+        let mut table = Table {
+            a: Vec::with_capacity(rows),
+            b: Vec::with_capacity(rows),
+            c: Vec::with_capacity(rows),
+        };
+        for line in input.lines() {
+            let [a, b, c] = match quick_line(line.as_bytes()) {
+                Some(sides) => sides,
+                None => {
+                    let sides = day3::parse_line(input, line)?;
+                    [sides[0], sides[1], sides[2]]
+                },
+            };
+            table.a.push(a);
+            table.b.push(b);
+            table.c.push(c);
+        }
+        Ok(table)
+    }
+
+    // The candidates read with any grouping, as day 3's candidates() reads
+    // them from the rows, or the first row of a final block that isn't full
+    pub fn grouped(&self, grouping: Grouping) -> Result<Table, usize> {
+        let height = grouping.height().unwrap_or(self.a.len());
+        if height == 0 {
+            return Ok(Table::default());
+        }
+        let blocks = self.a.len() / height;
+        if blocks * height < self.a.len() {
+            return Err(blocks * height);
+        }
+        let mut table = Table {
+            a: Vec::with_capacity(blocks * height),
+            b: Vec::with_capacity(blocks * height),
+            c: Vec::with_capacity(blocks * height),
+        };
+        let columns = [&self.a, &self.b, &self.c];
+        let mut down = (0..blocks).map(|k| k * height)
+            .flat_map(|row| columns.iter().flat_map(move |column| &column[row..row + height]));
+        while let (Some(&a), Some(&b), Some(&c)) = (down.next(), down.next(), down.next()) {
+            table.a.push(a);
+            table.b.push(b);
+            table.c.push(c);
+        }
+        Ok(table)
+    }
+}
+
+// The same test as Triangle::is_valid, a + b > c as a > c - b so nothing
+// overflows, with the subtraction saturating so there are no branches
+fn count_batch(a: &[u64], b: &[u64], c: &[u64]) -> usize {
+    a.iter().zip(b).zip(c)
+        .map(|((&a, &b), &c)| {
+            (a > c.saturating_sub(b)) as usize
+                & (b > a.saturating_sub(c)) as usize
+                & (c > b.saturating_sub(a)) as usize
+        })
+        .sum()
+}
+
+pub fn count_table(table: &Table) -> usize {
+    table.a.chunks(BATCH).zip(table.b.chunks(BATCH)).zip(table.c.chunks(BATCH))
+        .map(|((a, b), c)| count_batch(a, b, c))
+        .sum()
+}
+
+pub fn count_valid(engine: Engine, grouping: Grouping, input: &str) -> Result<usize, ParseError> {
+    match engine {
+        Engine::Lines => day3::count_valid(day3::candidates(input, grouping)),
+        Engine::Batch => {
+            let table = Table::parse(input)?.grouped(grouping)
+                .map_err(|row| day3::shape_error(input, row, Shape::Leftover))?;
+            Ok(count_table(&table))
+        },
+    }
+}
+
+// Bytes in each generated row
+const ROW: usize = 16;
+
+// Rows of three sides up to 999 like the puzzle's, from a fixed xorshift
+// sequence so every run times the same table
+pub fn generate(rows: usize) -> String {
+    let mut state = 0x2545_f491_4f6c_dd1du64;
+    let mut side = || {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        state % 999 + 1
+    };
+    let mut input = String::with_capacity(rows * ROW);
+    for _ in 0..rows {
+        input.push_str(&format!("{:>5}{:>5}{:>5}\n", side(), side(), side()));
+    }
+    input
+}
+
+pub fn compare(rows: usize, runs: usize, groupings: &[Grouping], engines: &[Engine]) {
+    let input = generate(rows);
+    println!("table of {} rows ({} MB)", rows, input.len() / 1_000_000);
+
+    for &grouping in groupings {
+        // leftover rows would be an error, so only time whole blocks
+        let whole = grouping.height().map_or(rows, |height| rows - rows % height);
+        println!();
+        if whole < rows {
+            println!("{}, reading the first {} rows", grouping, whole);
+        } else {
+            println!("{}", grouping);
+        }
+        let input = &input[..whole * ROW];
+
+        bench::compare(engines, runs, |engine| {
+            count_valid(engine, grouping, input).expect("generated tables always parse")
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn all_engines(grouping: Grouping, input: &str) -> Vec<Result<usize, ParseError>> {
+        bench::each(|e| count_valid(e, grouping, input))
+    }
+
+    #[test]
+    fn engines_agree() {
+        let input = generate(2940);
+        let groupings = [Grouping::Rows, Grouping::Columns(3), Grouping::Columns(7), Grouping::Transposed];
+        for &grouping in &groupings {
+            let counts = all_engines(grouping, &input);
+            assert!(counts[0].is_ok());
+            assert_eq!(counts[0], counts[1]);
+        }
+
+        // a tab, a plus sign and sides at the limit
+        let input = "3\t4 5\n+5 5 5\n18446744073709551615 18446744073709551615 1\n";
+        assert_eq!(all_engines(Grouping::Rows, input), vec![Ok(3), Ok(3)]);
+        assert_eq!(all_engines(Grouping::Columns(3), input), vec![Ok(1), Ok(1)]);
+        assert_eq!(all_engines(Grouping::Transposed, ""), vec![Ok(0), Ok(0)]);
+    }
+
+    #[test]
+    fn engines_agree_on_errors() {
+        for input in &["3 4 5\n3 0 5\n", "3 4 5\n3 4\n", "1 2 3 4", "3 4 99999999999999999999"] {
+            let errors = all_engines(Grouping::Rows, input);
+            assert!(errors[0].is_err());
+            assert_eq!(errors[0], errors[1]);
+        }
+
+        // a short final block, and a bad side after it winning over it
+        for input in &["3 4 5\n3 4 5\n3 4 5\n1 1 1\n", "3 4 5\n3 4 5\n3 4 5\n1 1 1\n1 x 1\n"] {
+            let errors = all_engines(Grouping::Columns(3), input);
+            assert!(errors[0].is_err());
+            assert_eq!(errors[0], errors[1]);
+        }
+    }
+
+    #[test]
+    fn batches_match_triangles() {
+        let table = Table::parse(&generate(1000)).unwrap();
+        assert_eq!(table.a.len(), 1000);
+        let expected = (0..table.a.len())
+            .filter(|&i| day3::Triangle { a: table.a[i], b: table.b[i], c: table.c[i] }.is_valid())
+            .count();
+        assert_eq!(count_table(&table), expected);
+    }
+}
@@ -0,0 +1,160 @@
+use day3::{Angles, Kind, Sides, Triangle};
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Format {
+    Table,
+    Csv,
+}
+
+impl Format {
+    pub fn from_name(name: &str) -> Option<Format> {
+        match name {
+            "table" => Some(Format::Table),
+            "csv" => Some(Format::Csv),
+            _ => None,
+        }
+    }
+}
+
+// Heron's formula has no answer for an impossible triangle
+fn area(t: &Triangle) -> Option<f64> {
+    match t.kind() {
+        Kind::Invalid => None,
+        _ => Some(t.area()),
+    }
+}
+
+// One row per candidate, numbered from 1 in the order they were read
+pub fn rows(triangles: &[Triangle], format: Format) -> Vec<String> {
+    let mut out = Vec::with_capacity(triangles.len() + 1);
+    match format {
+        Format::Table => out.push(format!("{:>6}  {:>5} {:>5} {:>5}  {:<18} {:>9} {:>14}",
+            "#", "a", "b", "c", "class", "perimeter", "area")),
+        Format::Csv => out.push("n,a,b,c,class,perimeter,area".to_string()),
+    }
+
+    for (i, t) in triangles.iter().enumerate() {
+        let area = match area(t) {
+            Some(area) => format!("{:.2}", area),
+            None => match format {
+                Format::Table => "-".to_string(),
+                Format::Csv => String::new(),
+            },
+        };
+        out.push(match format {
+            Format::Table => format!("{:>6}  {:>5} {:>5} {:>5}  {:<18} {:>9} {:>14}",
+                i + 1, t.a, t.b, t.c, t.kind(), t.perimeter(), area),
+            Format::Csv => format!("{},{},{},{},{},{},{}",
+                i + 1, t.a, t.b, t.c, t.kind(), t.perimeter(), area),
+        });
+    }
+    out
+}
+
+fn spread(name: &str, values: &[f64]) -> String {
+    if values.is_empty() {
+        return format!("{:<12}none", name);
+    }
+    let min = values.iter().cloned().fold(f64::INFINITY, f64::min);
+    let max = values.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+    let mean = values.iter().sum::<f64>() / values.len() as f64;
+    format!("{:<12}min {:.2}  mean {:.2}  max {:.2}", name, min, mean, max)
+}
+
+// Perimeters are whole numbers, so they're summarised exactly, the mean
+// rounded to hundredths with integer division rather than through a float
+fn perimeter_spread(perimeters: &[u128]) -> String {
+    let (min, max) = match (perimeters.iter().min(), perimeters.iter().max()) {
+        (Some(min), Some(max)) => (min, max),
+        _ => return format!("{:<12}none", "perimeter"),
+    };
+    let n = perimeters.len() as u128;
+    let sum: u128 = perimeters.iter().sum();
+    let cents = (sum / n) * 100 + (sum % n * 100 + n / 2) / n;
+    format!("{:<12}min {}  mean {}.{:02}  max {}", "perimeter", min, cents / 100, cents % 100, max)
+}
+
+// How many candidates fall in each class, then perimeter and area over the
+// valid triangles alone, leaving out the flat degenerate ones
+pub fn summary(triangles: &[Triangle]) -> Vec<String> {
+    let kinds: Vec<Kind> = triangles.iter().map(Triangle::kind).collect();
+    let count = |f: &dyn Fn(Kind) -> bool| kinds.iter().filter(|&&k| f(k)).count();
+    let sides = |s| move |k| match k { Kind::Valid(x, _) => x == s, _ => false };
+    let angles = |a| move |k| match k { Kind::Valid(_, x) => x == a, _ => false };
+
+    let classes: [(&str, usize); 8] = [
+        ("invalid", count(&|k| k == Kind::Invalid)),
+        ("degenerate", count(&|k| k == Kind::Degenerate)),
+        ("scalene", count(&sides(Sides::Scalene))),
+        ("isosceles", count(&sides(Sides::Isosceles))),
+        ("equilateral", count(&sides(Sides::Equilateral))),
+        ("acute", count(&angles(Angles::Acute))),
+        ("right", count(&angles(Angles::Right))),
+        ("obtuse", count(&angles(Angles::Obtuse))),
+    ];
+
+    let valid: Vec<&Triangle> = triangles.iter()
+        .filter(|t| matches!(t.kind(), Kind::Valid(..)))
+        .collect();
+    let perimeters: Vec<u128> = valid.iter().map(|t| t.perimeter()).collect();
+    let areas: Vec<f64> = valid.iter().map(|t| t.area()).collect();
+
+    let mut out = vec![format!("{:<12}{}", "candidates", triangles.len())];
+    out.extend(classes.iter().map(|&(name, n)| format!("{:<12}{}", name, n)));
+    out.push(String::new());
+    out.push(perimeter_spread(&perimeters));
+    out.push(spread("area", &areas));
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn triangles() -> Vec<Triangle> {
+        vec![
+            Triangle { a: 3, b: 4, c: 5 },
+            Triangle { a: 5, b: 10, c: 25 },
+            Triangle { a: 1, b: 2, c: 3 },
+            Triangle { a: 2, b: 2, c: 2 },
+        ]
+    }
+
+    #[test]
+    fn csv_rows() {
+        assert_eq!(rows(&triangles(), Format::Csv), vec![
+            "n,a,b,c,class,perimeter,area",
+            "1,3,4,5,scalene right,12,6.00",
+            "2,5,10,25,invalid,40,",
+            "3,1,2,3,degenerate,6,0.00",
+            "4,2,2,2,equilateral acute,6,1.73",
+        ]);
+    }
+
+    #[test]
+    fn exact_perimeters() {
+        let big = 18446744073709551615;
+        let triangles = [Triangle { a: big, b: big, c: big }, Triangle { a: big, b: big, c: big - 1 }];
+        assert_eq!(summary(&triangles)[10],
+            "perimeter   min 55340232221128654844  mean 55340232221128654844.50  max 55340232221128654845");
+        assert_eq!(summary(&[])[10], "perimeter   none");
+    }
+
+    #[test]
+    fn summary_counts_classes() {
+        assert_eq!(summary(&triangles()), vec![
+            "candidates  4",
+            "invalid     1",
+            "degenerate  1",
+            "scalene     1",
+            "isosceles   0",
+            "equilateral 1",
+            "acute       1",
+            "right       1",
+            "obtuse      0",
+            "",
+            "perimeter   min 6  mean 9.00  max 12",
+            "area        min 1.73  mean 3.87  max 6.00",
+        ]);
+    }
+}
@@ -0,0 +1,203 @@
+use std::collections::HashSet;
+use std::fmt;
+
+use day1::{self, Rotation};
+use parse_error::ParseError;
+
+// A more general day 1 navigator for grid and voxel puzzles. The compass
+// grammar is day 1's own "R2, L3"; the turtle grammar spells each step out:
+//
+//     L<deg>, R<deg>   turn on the spot by a multiple of 45 degrees
+//     F<n>, B<n>       move forwards, or backwards without turning
+//     U<n>, D<n>       climb or descend
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Grammar {
+    Compass,
+    Turtle,
+}
+
+impl Grammar {
+    pub fn from_name(name: &str) -> Option<Grammar> {
+        match name {
+            "compass" => Some(Grammar::Compass),
+            "turtle" => Some(Grammar::Turtle),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Instruction {
+    // eighths of a turn, clockwise
+    Turn(i32),
+    Forward(i32),
+    Back(i32),
+    Up(i32),
+    Down(i32),
+}
+
+// Eighths of a turn clockwise from north, so diagonal headings stay on the grid
+#[derive(Copy, Clone, Debug, PartialEq)]
+struct Heading(i32);
+
+impl Heading {
+    fn turn(self, eighths: i32) -> Heading {
+        Heading((self.0 + eighths).rem_euclid(8))
+    }
+
+    fn unit(self) -> (i32, i32) {
+        match self.0 {
+            0 => (0, 1),
+            1 => (1, 1),
+            2 => (1, 0),
+            3 => (1, -1),
+            4 => (0, -1),
+            5 => (-1, -1),
+            6 => (-1, 0),
+            _ => (-1, 1),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Point3 {
+    pub x: i32,
+    pub y: i32,
+    pub z: i32,
+}
+
+impl Point3 {
+    pub fn distance(self) -> i32 {
+        self.x.abs() + self.y.abs() + self.z.abs()
+    }
+}
+
+impl fmt::Display for Point3 {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}, {}, {}", self.x, self.y, self.z)
+    }
+}
+
+fn parse_instruction(input: &str, token: &str) -> Result<Instruction, ParseError> {
+    let token = token.trim();
+    let mut chars = token.chars();
+    let op = match chars.next() {
+        Some(c) => c,
+        None => return Err(ParseError::at(input, token, "an instruction like 'F2' or 'L45'")),
+    };
+    let arg = chars.as_str();
+    let n = match arg.parse::<i32>() {
+        Ok(n) if n >= 0 => Some(n),
+        _ => None,
+    };
+
+    match (op, n) {
+        ('L', Some(deg)) | ('R', Some(deg)) if deg % 45 == 0 =>
+            Ok(Instruction::Turn(if op == 'L' { -deg / 45 } else { deg / 45 })),
+        ('L', _) | ('R', _) => Err(ParseError::at(input, arg, "an angle in multiples of 45")),
+        ('F', Some(n)) => Ok(Instruction::Forward(n)),
+        ('B', Some(n)) => Ok(Instruction::Back(n)),
+        ('U', Some(n)) => Ok(Instruction::Up(n)),
+        ('D', Some(n)) => Ok(Instruction::Down(n)),
+        ('F', None) | ('B', None) | ('U', None) | ('D', None) =>
+            Err(ParseError::at(input, arg, "a distance")),
+        _ => Err(ParseError::at(input, &token[..op.len_utf8()], "'L', 'R', 'F', 'B', 'U' or 'D'")),
+    }
+}
+
+pub fn parse(input: &str, grammar: Grammar) -> Result<Vec<Instruction>, ParseError> {
+    match grammar {
+        Grammar::Compass => {
+            let mut instructions = Vec::new();
+            for (rotation, distance) in day1::parse_movements(input)? {
+                instructions.push(Instruction::Turn(match rotation {
+                    Rotation::Left => -2,
+                    Rotation::Right => 2,
+                }));
+                instructions.push(Instruction::Forward(distance));
+            }
+            Ok(instructions)
+        },
+        Grammar::Turtle => input.split(',').map(|t| parse_instruction(input, t)).collect(),
+    }
+}
+
+pub struct Walk {
+    pub end: Point3,
+    pub first_revisit: Option<Point3>,
+}
+
+// Every block is visited, diagonal steps included, so crossings are
+// found the same way as day 1 part 2
+pub fn walk(instructions: &[Instruction]) -> Walk {
+    let mut position = Point3 { x: 0, y: 0, z: 0 };
+    let mut heading = Heading(0);
+    let mut visited = HashSet::new();
+    let mut first_revisit = None;
+    visited.insert(position);
+
+    for &instruction in instructions {
+        let (dx, dy) = heading.unit();
+        let (step, n) = match instruction {
+            Instruction::Turn(eighths) => {
+                heading = heading.turn(eighths);
+                continue;
+            },
+            Instruction::Forward(n) => ((dx, dy, 0), n),
+            Instruction::Back(n) => ((-dx, -dy, 0), n),
+            Instruction::Up(n) => ((0, 0, 1), n),
+            Instruction::Down(n) => ((0, 0, -1), n),
+        };
+
+        for _ in 0..n {
+            position = Point3 {
+                x: position.x + step.0,
+                y: position.y + step.1,
+                z: position.z + step.2,
+            };
+            if !visited.insert(position) && first_revisit.is_none() {
+                first_revisit = Some(position);
+            }
+        }
+    }
+
+    Walk { end: position, first_revisit }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn point(x: i32, y: i32, z: i32) -> Point3 {
+        Point3 { x, y, z }
+    }
+
+    #[test]
+    fn compass_matches_day1() {
+        let walked = walk(&parse("R8, R4, R4, R8", Grammar::Compass).unwrap());
+        assert_eq!(walked.end, point(4, 4, 0));
+        assert_eq!(walked.first_revisit, Some(point(4, 0, 0)));
+        assert_eq!(walk(&parse("R5, L5, R5, R3", Grammar::Compass).unwrap()).end.distance(), 12);
+    }
+
+    #[test]
+    fn turtle_moves() {
+        let instructions = parse("L45, F2, R135, B1", Grammar::Turtle).unwrap();
+        assert_eq!(instructions[0], Instruction::Turn(-1));
+        assert_eq!(walk(&instructions).end, point(-3, 2, 0));
+
+        let walked = walk(&parse("U2, F1, D2, B1", Grammar::Turtle).unwrap());
+        assert_eq!(walked.end, point(0, 0, 0));
+        assert_eq!(walked.first_revisit, Some(point(0, 0, 0)));
+    }
+
+    #[test]
+    fn bad_turtle_instructions() {
+        let e = parse("F2, L30", Grammar::Turtle).unwrap_err();
+        assert_eq!((e.line, e.column, e.token.as_str()), (1, 6, "30"));
+        let e = parse("F2, X1", Grammar::Turtle).unwrap_err();
+        assert_eq!((e.line, e.column, e.token.as_str()), (1, 5, "X"));
+        let e = parse("U", Grammar::Turtle).unwrap_err();
+        assert_eq!((e.line, e.column, e.token.as_str()), (1, 2, ""));
+    }
+}
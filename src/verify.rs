@@ -0,0 +1,90 @@
+use std::time::{Duration, Instant};
+
+use answers::Answers;
+use provider::Provider;
+use registry::Day;
+use solver::{self, Answer, Part};
+use timing::format_duration;
+
+enum Status {
+    Pass,
+    Fail { expected: Answer },
+    Unrecorded,
+    Error(String),
+}
+
+// 65556 and "65556" are the same answer to a puzzle, so answers are
+// compared as they're printed rather than by type
+fn same(expected: &Answer, answer: &Answer) -> bool {
+    expected.to_string() == answer.to_string()
+}
+
+fn check(day: &Day, input: &str, part: Part, answers: &Answers) -> (Status, Option<Answer>, Duration) {
+    let start = Instant::now();
+    let result = day.solver.solve(part, input);
+    let elapsed = start.elapsed();
+
+    match result {
+        Err(e) => (Status::Error(solver::brief_error(&e)), None, elapsed),
+        Ok(answer) => {
+            let status = match answers.get(day.day, part) {
+                None => Status::Unrecorded,
+                Some(expected) if same(expected, &answer) => Status::Pass,
+                Some(expected) => Status::Fail { expected: expected.clone() },
+            };
+            (status, Some(answer), elapsed)
+        },
+    }
+}
+
+// Runs every part of every given day against its recorded answer and prints
+// a table of the results. Returns false if anything failed or errored.
+pub fn verify(days: &[&Day], provider: &Provider, answers: &Answers) -> bool {
+    let mut ok = true;
+    println!("day  part  status        time  answer");
+
+    for day in days {
+        let input = match provider.input(day.day) {
+            Ok(input) => input,
+            Err(e) => {
+                ok = false;
+                println!("{:>3}     -  ERROR            -  {}", day.day, e);
+                continue;
+            },
+        };
+
+        for &part in &[Part::One, Part::Two] {
+            let (status, answer, elapsed) = check(day, &input, part, answers);
+            let answer = answer.as_ref().map_or(String::new(), Answer::brief);
+            let (label, detail) = match status {
+                Status::Pass => ("pass", answer),
+                Status::Unrecorded => ("----", format!("{} (no recorded answer)", answer)),
+                Status::Fail { expected } => {
+                    ok = false;
+                    ("FAIL", format!("{}, expected {}", answer, expected.brief()))
+                },
+                Status::Error(e) => {
+                    ok = false;
+                    ("ERROR", e)
+                },
+            };
+            println!("{:>3}  {:>4}  {:<6} {:>10}  {}",
+                day.day, part, label, format_duration(elapsed), detail);
+        }
+    }
+
+    ok
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn answers_match_as_printed() {
+        assert!(same(&Answer::Integer(65556), &Answer::Text("65556".to_string())));
+        assert!(same(&Answer::Text("CB779".to_string()), &Answer::Text("CB779".to_string())));
+        assert!(!same(&Answer::Integer(65556), &Answer::Text("65557".to_string())));
+        assert!(!same(&Answer::Grid(vec!["#.".to_string()]), &Answer::Grid(vec![".#".to_string()])));
+    }
+}